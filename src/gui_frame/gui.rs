@@ -1,11 +1,24 @@
 use eframe::{egui, egui::IconData};
+use egui_plot::{Bar, BarChart, Line, Plot, PlotBounds, PlotPoint, Points, VLine};
 use image::{self, GenericImageView, GrayImage};
 use rfd::FileDialog;
 use std::sync::Arc;
 
 use crate::image_util::*;
 
+use self::cluster::*;
+use self::color::*;
+use self::colormap::*;
+use self::components::*;
+use self::contour::*;
+use self::edge::*;
 use self::image_util::*;
+use self::lut3d::*;
+use self::morphology::*;
+use self::region::*;
+use self::threshold::*;
+use self::transform::*;
+use self::watershed::*;
 
 pub fn run() {
     // 创建窗口默认配置
@@ -51,6 +64,38 @@ fn load_fonts(ctx: &egui::Context) {
     ctx.set_fonts(fonts);
 }
 
+// 边界处理方式名称
+fn border_mode_text(mode: BorderMode) -> &'static str {
+    match mode {
+        BorderMode::Constant(_) => "常数",
+        BorderMode::Replicate => "复制",
+        BorderMode::Reflect => "反射",
+        BorderMode::Wrap => "环绕",
+    }
+}
+
+// 阈值选取方法名称，None 为手动
+fn threshold_method_text(method: Option<ThresholdMethod>) -> &'static str {
+    match method {
+        None => "手动",
+        Some(ThresholdMethod::Otsu) => "Otsu",
+        Some(ThresholdMethod::Triangle) => "三角法",
+        Some(ThresholdMethod::Kapur) => "Kapur熵",
+        Some(ThresholdMethod::IsoData) => "迭代均值",
+        Some(ThresholdMethod::Huang) => "Huang",
+    }
+}
+
+fn equalize_mode_text(mode: Option<EqualizeMode>) -> &'static str {
+    match mode {
+        None => "灰度",
+        Some(EqualizeMode::Hsv) => "HSV明度",
+        Some(EqualizeMode::Lab) => "Lab亮度",
+        Some(EqualizeMode::YCbCr) => "YCbCr亮度",
+        Some(EqualizeMode::Rgb) => "RGB分通道",
+    }
+}
+
 struct ImageApp {
     string_values: Vec<String>,
     frame_count: u32,
@@ -62,6 +107,92 @@ struct ImageApp {
     brightness_value: i32,
     blur_value: f32,
     segmentation_index: u8,
+    smooth_radius: u32,
+    border_mode: BorderMode,
+    process_alpha: bool,
+    gray_level: u16,
+    kernel_size: usize,
+    kernel_values: Vec<f32>,
+    kernel_normalize: bool,
+    edge_operator: GradientOperator,
+    log_value: [f32; 2],
+    canny_value: [f32; 3],
+    unsharp_value: [f32; 3],
+    high_boost_value: [f32; 2],
+    threshold_method: Option<ThresholdMethod>,
+    multi_otsu_classes: usize,
+    multi_otsu_thresholds: Vec<u8>,
+    local_threshold_method: LocalThresholdMethod,
+    local_threshold_value: [f32; 2],
+    morphology_operation: MorphologyOperation,
+    element_shape: usize,
+    element_size: usize,
+    element_custom: Vec<bool>,
+    connectivity: Connectivity,
+    component_stats: Vec<ComponentStats>,
+    component_error: Option<String>,
+    component_sort: (usize, bool),
+    show_components: bool,
+    contours: Vec<Contour>,
+    contour_error: Option<String>,
+    contour_epsilon: f32,
+    contour_shape: usize,
+    watershed_source: usize,
+    watershed_depth: f32,
+    watershed_labels: bool,
+    cluster_labels: bool,
+    paint_markers: bool,
+    marker_strokes: Vec<Vec<(i32, i32)>>,
+    cluster_k: usize,
+    cluster_space: ClusterSpace,
+    cluster_seed: u64,
+    mean_shift_value: [f32; 2],
+    cluster_centers: Vec<[f32; 3]>,
+    magic_wand: bool,
+    wand_tolerance: f32,
+    selection: GrayImage,
+    gray_standard: GrayStandard,
+    color_space: ColorSpace,
+    color_channel: usize,
+    equalize_mode: Option<EqualizeMode>,
+    clahe_tiles: [usize; 2],
+    clahe_clip: f32,
+    reference_image: Option<image::DynamicImage>,
+    reference_error: Option<String>,
+    target_distribution: Vec<f32>,
+    target_point: Option<(usize, f32)>,
+    transform_kind: usize,
+    intensity_value: [f32; 4],
+    bit_plane: u32,
+    piecewise_points: Vec<[f32; 2]>,
+    curve_channel: usize,
+    curve_points: Vec<Vec<[f64; 2]>>,
+    curve_drag: Option<usize>,
+    show_levels: bool,
+    levels_value: [f32; 5],
+    levels_histogram: [u32; 256],
+    lut3d: Option<Lut3d>,
+    lut_interpolation: LutInterpolation,
+    lut_size: usize,
+    lut_error: Option<String>,
+    wb_gains: [f32; 3],
+    wb_percentile: f32,
+    wb_temperature: f32,
+    wb_tint: f32,
+    pick_neutral: bool,
+    color_matrix: [[f32; 3]; 3],
+    saturation_value: f32,
+    saturation_space: ColorSpace,
+    hue_value: f32,
+    vibrance_value: f32,
+    channel_mixer: [[f32; 3]; 3],
+    mixer_monochrome: bool,
+    colormap: Colormap,
+    colormap_auto: bool,
+    colormap_range: (f32, f32),
+    colormap_error: Option<String>,
+    output_error: Option<String>,
+    preview_texture: Option<egui::TextureHandle>,
 }
 
 impl ImageApp {
@@ -83,6 +214,90 @@ impl ImageApp {
                 "图像平滑".to_string(),       //10
                 "图像锐化".to_string(),       //11
                 "图像分割".to_string(),       //12
+                "平滑半径".to_string(),       //13
+                "边界处理".to_string(),       //14
+                "处理透明通道".to_string(),   //15
+                "灰度上限".to_string(),       //16
+                "卷积核编辑".to_string(),     //17
+                "归一化".to_string(),         //18
+                "应用卷积".to_string(),       //19
+                "边缘检测".to_string(),       //20
+                "梯度幅值".to_string(),       //21
+                "梯度方向".to_string(),       //22
+                "LoG过零点".to_string(),      //23
+                "低阈值".to_string(),         //24
+                "高阈值".to_string(),         //25
+                "反锐化掩模".to_string(),     //26
+                "高提升滤波".to_string(),     //27
+                "自动".to_string(),           //28
+                "多阈值Otsu".to_string(),     //29
+                "局部阈值".to_string(),       //30
+                "窗口大小".to_string(),       //31
+                "形态学处理".to_string(),     //32
+                "结构元素".to_string(),       //33
+                "应用于当前结果".to_string(), //34
+                "连通域标记".to_string(),     //35
+                "连通域统计".to_string(),     //36
+                "导出CSV".to_string(),        //37
+                "轮廓提取".to_string(),       //38
+                "简化阈值".to_string(),       //39
+                "导出轮廓".to_string(),       //40
+                "分水岭分割".to_string(),     //41
+                "极小值深度".to_string(),     //42
+                "手绘标记".to_string(),       //43
+                "清除标记".to_string(),       //44
+                "显示标记图".to_string(),     //45
+                "K均值聚类".to_string(),      //46
+                "随机种子".to_string(),       //47
+                "均值漂移".to_string(),       //48
+                "空间/颜色带宽".to_string(),  //49
+                "魔棒选区".to_string(),       //50
+                "容差".to_string(),           //51
+                "选区掩模".to_string(),       //52
+                "清除选区".to_string(),       //53
+                "颜色空间".to_string(),       //54
+                "显示通道".to_string(),       //55
+                "CLAHE".to_string(),          //56
+                "横向子块".to_string(),       //57
+                "纵向子块".to_string(),       //58
+                "裁剪上限".to_string(),       //59
+                "直方图匹配".to_string(),     //60
+                "选择参考图像".to_string(),   //61
+                "匹配参考图像".to_string(),   //62
+                "匹配目标分布".to_string(),   //63
+                "重置分布".to_string(),       //64
+                "强度变换".to_string(),       //65
+                "应用变换".to_string(),       //66
+                "添加控制点".to_string(),     //67
+                "曲线".to_string(),           //68
+                "色阶".to_string(),           //69
+                "重置曲线".to_string(),       //70
+                "应用色阶".to_string(),       //71
+                "3D LUT".to_string(),         //72
+                "加载LUT".to_string(),        //73
+                "应用LUT".to_string(),        //74
+                "导出当前调整".to_string(),   //75
+                "白平衡".to_string(),         //76
+                "灰度世界".to_string(),       //77
+                "白块".to_string(),           //78
+                "百分位".to_string(),         //79
+                "点击取中性色".to_string(),   //80
+                "色温".to_string(),           //81
+                "色调".to_string(),           //82
+                "重置白平衡".to_string(),     //83
+                "颜色矩阵".to_string(),       //84
+                "应用矩阵".to_string(),       //85
+                "重置矩阵".to_string(),       //86
+                "饱和度".to_string(),         //87
+                "色相旋转".to_string(),       //88
+                "自然饱和度".to_string(),     //89
+                "通道混合器".to_string(),     //90
+                "单色".to_string(),           //91
+                "重置通道混合器".to_string(), //92
+                "伪彩色".to_string(),         //93
+                "自动范围".to_string(),       //94
+                "应用伪彩色".to_string(),     //95
+                "保存色标".to_string(),       //96
             ],
             frame_count: 0,
             image_file_path: String::new(),
@@ -93,6 +308,934 @@ impl ImageApp {
             brightness_value: 0,
             blur_value: 0.0,
             segmentation_index: 0,
+            smooth_radius: 1,
+            border_mode: BorderMode::Reflect,
+            process_alpha: false,
+            gray_level: 65535,
+            kernel_size: 3,
+            kernel_values: Kernel::box_filter(3).weights(),
+            kernel_normalize: true,
+            edge_operator: GradientOperator::Sobel,
+            log_value: [2.0, 1.0],
+            canny_value: [1.4, 50.0, 150.0],
+            unsharp_value: [2.0, 1.0, 0.0],
+            high_boost_value: [2.0, 1.5],
+            threshold_method: None,
+            multi_otsu_classes: 3,
+            multi_otsu_thresholds: Vec::new(),
+            local_threshold_method: LocalThresholdMethod::Sauvola,
+            local_threshold_value: [31.0, LocalThresholdMethod::Sauvola.default_k()],
+            morphology_operation: MorphologyOperation::Open,
+            element_shape: 0,
+            element_size: 3,
+            element_custom: vec![true; 9],
+            connectivity: Connectivity::Eight,
+            component_stats: Vec::new(),
+            component_error: None,
+            component_sort: (0, true),
+            show_components: false,
+            contours: Vec::new(),
+            contour_error: None,
+            contour_epsilon: 1.0,
+            contour_shape: 0,
+            watershed_source: 1,
+            watershed_depth: 1.0,
+            watershed_labels: false,
+            cluster_labels: false,
+            paint_markers: false,
+            marker_strokes: Vec::new(),
+            cluster_k: 4,
+            cluster_space: ClusterSpace::Lab,
+            cluster_seed: 0,
+            mean_shift_value: [8.0, 16.0],
+            cluster_centers: Vec::new(),
+            magic_wand: false,
+            wand_tolerance: 32.0,
+            selection: GrayImage::new(0, 0),
+            gray_standard: GrayStandard::Bt601,
+            color_space: ColorSpace::Hsv,
+            color_channel: 0,
+            equalize_mode: None,
+            clahe_tiles: [8, 8],
+            clahe_clip: 2.0,
+            reference_image: None,
+            reference_error: None,
+            target_distribution: vec![0.5; 256],
+            target_point: None,
+            transform_kind: 0,
+            intensity_value: [0.5, 10.0, 1.0, 99.0],
+            bit_plane: 7,
+            piecewise_points: vec![[0.0, 0.0], [0.3, 0.1], [0.7, 0.9], [1.0, 1.0]],
+            curve_channel: 0,
+            curve_points: vec![vec![[0.0, 0.0], [255.0, 255.0]]; 4],
+            curve_drag: None,
+            show_levels: false,
+            levels_value: [0.0, 255.0, 1.0, 0.0, 255.0],
+            levels_histogram: [0; 256],
+            lut3d: None,
+            lut_interpolation: LutInterpolation::Tetrahedral,
+            lut_size: 33,
+            lut_error: None,
+            wb_gains: [1.0; 3],
+            wb_percentile: 99.0,
+            wb_temperature: 6500.0,
+            wb_tint: 0.0,
+            pick_neutral: false,
+            color_matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            saturation_value: 1.0,
+            saturation_space: ColorSpace::Hsl,
+            hue_value: 0.0,
+            vibrance_value: 0.0,
+            channel_mixer: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            mixer_monochrome: false,
+            colormap: Colormap::Viridis,
+            colormap_auto: true,
+            colormap_range: (0.0, 1.0),
+            colormap_error: None,
+            output_error: None,
+            preview_texture: None,
+        }
+    }
+
+    // 写入缓存文件，失败时记录错误信息并返回 false
+    fn write_cache(&mut self, image: &image::DynamicImage, file: &str) -> bool {
+        match image_output(image, file) {
+            Ok(()) => {
+                self.output_error = None;
+                true
+            }
+            Err(e) => {
+                self.output_error = Some(e);
+                false
+            }
+        }
+    }
+
+    // 保存处理结果并在结果面板中显示
+    fn output_result(&mut self, ctx: &egui::Context, image: &image::DynamicImage, file: &str) {
+        if !self.write_cache(image, file) {
+            return;
+        }
+        self.image_file_path = format!("./{}", file);
+
+        // 缓存文件名不变，需清除 egui 已加载的旧图片
+        ctx.forget_image(&format!("file://{}", self.image_file_path));
+    }
+
+    // Canny 边缘检测
+    fn canny_output(&mut self, ctx: &egui::Context) {
+        let [sigma, low, high] = self.canny_value;
+        let image = canny(&self.image_data, sigma, low, high, self.border_mode);
+
+        self.output_result(ctx, &image::DynamicImage::ImageLuma8(image), "cache14.png");
+    }
+
+    // 反锐化掩模
+    fn unsharp_output(&mut self, ctx: &egui::Context) {
+        let [sigma, amount, threshold] = self.unsharp_value;
+        let image = unsharp_mask_image(&self.image_data, sigma, amount, threshold as u8);
+
+        self.output_result(ctx, &image, "cache15.png");
+    }
+
+    // 高提升滤波
+    fn high_boost_output(&mut self, ctx: &egui::Context) {
+        let [sigma, boost] = self.high_boost_value;
+        let image = high_boost_image(&self.image_data, sigma, boost);
+
+        self.output_result(ctx, &image, "cache16.png");
+    }
+
+    // 轮廓提取，在原图上叠加轮廓或其外接形状
+    fn contour_output(&mut self, ctx: &egui::Context) {
+        let binary = self.current_result().to_luma8();
+        self.contours = find_contours(&binary);
+
+        let epsilon = self.contour_epsilon as f64;
+        if epsilon > 0.0 {
+            for contour in self.contours.iter_mut() {
+                contour.points = simplify_contour(&contour.points, epsilon);
+            }
+        }
+
+        let red = image::Rgba([255, 0, 0, 255]);
+        let blue = image::Rgba([0, 0, 255, 255]);
+        let mut image = draw_contours(&self.image_data, &self.contours, red, blue);
+
+        // 外接形状只针对外边界
+        for contour in self.contours.iter().filter(|c| !c.is_hole) {
+            match self.contour_shape {
+                1 => draw_polygon(&mut image, &convex_hull(&contour.points), red),
+                2 => {
+                    let corners = min_area_rect(&contour.points)
+                        .corners()
+                        .map(|(x, y)| (x.round() as i32, y.round() as i32));
+                    draw_polygon(&mut image, &corners, red);
+                }
+                3 => draw_circle(&mut image, &min_enclosing_circle(&contour.points), red),
+                _ => {}
+            }
+        }
+
+        self.output_result(ctx, &image::DynamicImage::ImageRgba8(image), "cache21.png");
+    }
+
+    // 分水岭分割，有手绘标记时使用手绘标记，否则使用区域极小值
+    fn watershed_output(&mut self, ctx: &egui::Context) {
+        let (width, height) = self.image_data.dimensions();
+        let strokes = markers_from_strokes(&self.marker_strokes, width as usize, height as usize);
+        let markers = if strokes.count > 0 {
+            Some(&strokes)
+        } else {
+            None
+        };
+
+        let labels = match self.watershed_source {
+            0 => watershed_gradient(
+                &self.image_data,
+                markers,
+                self.watershed_depth,
+                self.border_mode,
+            ),
+            _ => watershed_distance(
+                &self.current_result().to_luma8(),
+                markers,
+                self.watershed_depth,
+            ),
+        };
+
+        let image = if self.watershed_labels {
+            image::DynamicImage::ImageRgb8(colorize_labels(&labels))
+        } else {
+            let red = image::Rgba([255, 0, 0, 255]);
+            image::DynamicImage::ImageRgba8(draw_watershed(&self.image_data, &labels, red))
+        };
+
+        self.output_result(ctx, &image, "cache22.png");
+    }
+
+    // 指针在结果图像上对应的像素坐标
+    fn pointer_pixel(&self, response: &egui::Response) -> Option<(i32, i32)> {
+        let (width, height) = self.image_data.dimensions();
+        let rect = response.rect;
+        let pos = response.interact_pointer_pos()?;
+        if width == 0 || height == 0 || !rect.contains(pos) {
+            return None;
+        }
+
+        Some((
+            (((pos.x - rect.min.x) / rect.width() * width as f32) as i32).min(width as i32 - 1),
+            (((pos.y - rect.min.y) / rect.height() * height as f32) as i32).min(height as i32 - 1),
+        ))
+    }
+
+    // 在结果面板上手绘标记，每次拖动为一条笔画
+    fn paint_strokes(&mut self, ui: &egui::Ui, response: &egui::Response) {
+        if !self.paint_markers {
+            return;
+        }
+
+        if let Some(point) = self.pointer_pixel(response) {
+            if response.drag_started() || response.clicked() {
+                self.marker_strokes.push(vec![point]);
+            } else if response.dragged() {
+                if let Some(stroke) = self.marker_strokes.last_mut() {
+                    if stroke.last() != Some(&point) {
+                        stroke.push(point);
+                    }
+                }
+            }
+        }
+
+        let (width, height) = self.image_data.dimensions();
+        let rect = response.rect;
+        let to_screen = |(x, y): (i32, i32)| {
+            egui::pos2(
+                rect.min.x + (x as f32 + 0.5) / width as f32 * rect.width(),
+                rect.min.y + (y as f32 + 0.5) / height as f32 * rect.height(),
+            )
+        };
+
+        let painter = ui.painter_at(rect);
+        let stroke = egui::Stroke::new(3.0, egui::Color32::GREEN);
+        for points in &self.marker_strokes {
+            let points: Vec<egui::Pos2> = points.iter().map(|p| to_screen(*p)).collect();
+            if points.len() == 1 {
+                painter.circle_filled(points[0], 2.0, egui::Color32::GREEN);
+            } else {
+                painter.add(egui::Shape::line(points, stroke));
+            }
+        }
+    }
+
+    // 显示聚类结果，勾选显示标记图时输出标记图，否则输出颜色量化图
+    fn cluster_output(&mut self, ctx: &egui::Context, result: Clustering) {
+        self.cluster_centers = result.centers;
+
+        let image = if self.cluster_labels {
+            colorize_labels(&result.labels)
+        } else {
+            result.image
+        };
+        self.output_result(ctx, &image::DynamicImage::ImageRgb8(image), "cache23.png");
+    }
+
+    // K均值颜色聚类
+    fn kmeans_output(&mut self, ctx: &egui::Context) {
+        let result = kmeans(
+            &self.image_data,
+            self.cluster_k,
+            self.cluster_space,
+            self.cluster_seed,
+            50,
+        );
+        self.cluster_output(ctx, result);
+    }
+
+    // 魔棒选区，按住 Shift 加选，按住 Alt 或 Ctrl 减选
+    fn magic_wand_click(&mut self, ctx: &egui::Context, response: &egui::Response) {
+        if !self.magic_wand || !response.clicked() {
+            return;
+        }
+
+        if let Some((x, y)) = self.pointer_pixel(response) {
+            let modifiers = ctx.input(|i| i.modifiers);
+            let mode = if modifiers.shift {
+                SelectionMode::Add
+            } else if modifiers.alt || modifiers.command {
+                SelectionMode::Subtract
+            } else {
+                SelectionMode::Replace
+            };
+
+            self.selection = magic_wand(
+                &self.image_data,
+                &self.selection,
+                (x as u32, y as u32),
+                self.wand_tolerance,
+                self.connectivity,
+                mode,
+            );
+
+            let image = draw_selection(
+                &self.image_data,
+                &self.selection,
+                image::Rgba([255, 0, 0, 255]),
+            );
+            self.output_result(ctx, &image::DynamicImage::ImageRgba8(image), "cache24.png");
+        }
+    }
+
+    // CLAHE，均衡化方式为灰度时输出灰度图
+    fn clahe_output(&mut self, ctx: &egui::Context) {
+        let tiles = (self.clahe_tiles[0], self.clahe_tiles[1]);
+        let image = match self.equalize_mode {
+            Some(mode) => clahe_color_image(&self.image_data, tiles, self.clahe_clip, mode),
+            None => image::DynamicImage::ImageLuma8(clahe_gray_image(
+                &self.image_data,
+                tiles,
+                self.clahe_clip,
+            )),
+        };
+
+        self.output_result(ctx, &image, "cache27.png");
+    }
+
+    // 按均衡化方式选择待处理图像，方式为灰度时先转为灰度图
+    fn processing_source(&self) -> (image::DynamicImage, EqualizeMode) {
+        match self.equalize_mode {
+            Some(mode) => (self.image_data.clone(), mode),
+            None => {
+                let (width, height) = self.image_data.dimensions();
+                let gray =
+                    GrayImage::from_vec(width, height, image_to_gray(&self.image_data)).unwrap();
+                (image::DynamicImage::ImageLuma8(gray), EqualizeMode::YCbCr)
+            }
+        }
+    }
+
+    // 直方图匹配
+    fn histogram_matching_output(&mut self, ctx: &egui::Context, reference: bool) {
+        let (image, mode) = self.processing_source();
+
+        let image = if reference {
+            match &self.reference_image {
+                Some(reference_image) => match_histogram(&image, reference_image, mode),
+                None => return,
+            }
+        } else {
+            let target: Vec<f64> = self.target_distribution.iter().map(|v| *v as f64).collect();
+            match_distribution(&image, &target, mode)
+        };
+
+        self.output_result(ctx, &image, "cache28.png");
+    }
+
+    // 目标分布编辑，按住鼠标拖动绘制各灰度级的相对频数
+    fn distribution_editor(&mut self, ui: &mut egui::Ui) {
+        let (response, painter) =
+            ui.allocate_painter(egui::vec2(256.0, 100.0), egui::Sense::drag());
+        let rect = response.rect;
+        let levels = self.target_distribution.len();
+
+        if let Some(pos) = response.interact_pointer_pos() {
+            let x =
+                (((pos.x - rect.min.x) / rect.width() * levels as f32) as usize).min(levels - 1);
+            let v = ((rect.max.y - pos.y) / rect.height()).clamp(0.0, 1.0);
+
+            // 与上一点之间线性插值，快速拖动时不留空隙
+            let (x0, v0) = self.target_point.unwrap_or((x, v));
+            let steps = x0.abs_diff(x);
+            for k in 0..=steps {
+                let i = if x0 <= x { x0 + k } else { x0 - k };
+                let t = k as f32 / steps.max(1) as f32;
+                self.target_distribution[i] = v0 + (v - v0) * t;
+            }
+            self.target_point = Some((x, v));
+        }
+        if !response.dragged() {
+            self.target_point = None;
+        }
+
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        let stroke = egui::Stroke::new(1.0, ui.visuals().text_color());
+        for (i, v) in self.target_distribution.iter().enumerate() {
+            let x = rect.min.x + (i as f32 + 0.5) / levels as f32 * rect.width();
+            let y = rect.max.y - v * rect.height();
+            painter.line_segment([egui::pos2(x, rect.max.y), egui::pos2(x, y)], stroke);
+        }
+    }
+
+    // 当前选择的强度变换
+    fn intensity_transform(&self) -> IntensityTransform {
+        let [gamma, c, low, high] = self.intensity_value;
+        match self.transform_kind {
+            0 => IntensityTransform::Gamma(gamma),
+            1 => IntensityTransform::Log(c),
+            2 => IntensityTransform::InverseLog(c),
+            3 => IntensityTransform::PercentileStretch { low, high },
+            4 => IntensityTransform::BitPlane(self.bit_plane),
+            _ => IntensityTransform::Piecewise(
+                self.piecewise_points.iter().map(|p| (p[0], p[1])).collect(),
+            ),
+        }
+    }
+
+    // 强度变换，与直方图均衡化使用相同的处理方式
+    fn transform_output(&mut self, ctx: &egui::Context) {
+        let (image, mode) = self.processing_source();
+        let image = apply_transform(&image, &self.intensity_transform(), mode);
+
+        self.output_result(ctx, &image, "cache29.png");
+    }
+
+    // 某一通道曲线的查找表，0 为主曲线，1~3 为 R、G、B
+    fn curve_lut_of(&self, channel: usize) -> Vec<u8> {
+        let points: Vec<(f32, f32)> = self.curve_points[channel]
+            .iter()
+            .map(|p| (p[0] as f32, p[1] as f32))
+            .collect();
+        curve_lut(&points)
+    }
+
+    // 曲线调整
+    fn curves_output(&mut self, ctx: &egui::Context) {
+        let master = self.curve_lut_of(0);
+        let channels: Vec<Vec<u8>> = (1..4).map(|c| self.curve_lut_of(c)).collect();
+        let image = apply_luts(&self.image_data, &master, &channels);
+
+        self.output_result(ctx, &image, "cache30.png");
+    }
+
+    // 曲线编辑器：拖动控制点，在空白处拖动添加控制点，右键删除控制点
+    fn curves_editor(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let lut = self.curve_lut_of(self.curve_channel);
+        let color = match self.curve_channel {
+            1 => egui::Color32::RED,
+            2 => egui::Color32::GREEN,
+            3 => egui::Color32::BLUE,
+            _ => ui.visuals().text_color(),
+        };
+
+        let plot = Plot::new("curves")
+            .width(256.0)
+            .view_aspect(1.0)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .allow_boxed_zoom(false)
+            .allow_double_click_reset(false)
+            .show(ui, |plot_ui| {
+                plot_ui.set_plot_bounds(PlotBounds::from_min_max([0.0, 0.0], [255.0, 255.0]));
+                plot_ui.line(
+                    Line::new(
+                        lut.iter()
+                            .enumerate()
+                            .map(|(x, y)| [x as f64, *y as f64])
+                            .collect::<Vec<_>>(),
+                    )
+                    .color(color),
+                );
+                plot_ui.points(
+                    Points::new(self.curve_points[self.curve_channel].clone())
+                        .radius(4.0)
+                        .color(color),
+                );
+            });
+
+        let response = &plot.response;
+        let points = &mut self.curve_points[self.curve_channel];
+        let pointer = response.interact_pointer_pos();
+
+        // 屏幕距离最近的控制点
+        let nearest = |points: &[[f64; 2]], pos: egui::Pos2| {
+            points
+                .iter()
+                .enumerate()
+                .map(|(index, p)| {
+                    let screen = plot
+                        .transform
+                        .position_from_point(&PlotPoint::new(p[0], p[1]));
+                    (index, screen.distance(pos))
+                })
+                .filter(|(_, distance)| *distance < 8.0)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(index, _)| index)
+        };
+
+        if let Some(pos) = pointer {
+            let value = plot.transform.value_from_position(pos);
+            let value = [value.x.clamp(0.0, 255.0), value.y.clamp(0.0, 255.0)];
+
+            if response.drag_started() {
+                self.curve_drag = nearest(points, pos).or_else(|| {
+                    let index = points
+                        .iter()
+                        .position(|p| p[0] > value[0])
+                        .unwrap_or(points.len());
+                    points.insert(index, value);
+                    Some(index)
+                });
+            } else if response.dragged() {
+                if let Some(index) = self.curve_drag {
+                    // 控制点不能越过相邻控制点
+                    let min = if index > 0 {
+                        points[index - 1][0] + 1.0
+                    } else {
+                        0.0
+                    };
+                    let max = points.get(index + 1).map_or(255.0, |p| p[0] - 1.0);
+                    points[index] = [value[0].clamp(min, max.max(min)), value[1]];
+                }
+            }
+
+            if response.secondary_clicked() && points.len() > 2 {
+                if let Some(index) = nearest(points, pos) {
+                    points.remove(index);
+                    self.curves_output(ctx);
+                }
+            }
+        }
+
+        if response.drag_stopped() {
+            self.curve_drag = None;
+            self.curves_output(ctx);
+        }
+    }
+
+    // 色阶查找表
+    fn levels_lut_value(&self) -> Vec<u8> {
+        let [black, white, gamma, out_black, out_white] = self.levels_value;
+        levels_lut(
+            (black as u8, white as u8),
+            gamma,
+            (out_black as u8, out_white as u8),
+        )
+    }
+
+    // 色阶窗口，直方图为原图亮度，折线为调整后的直方图
+    fn levels_dialog(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let lut = self.levels_lut_value();
+        let mut output = [0_u32; 256];
+        for (v, count) in self.levels_histogram.iter().enumerate() {
+            output[lut[v] as usize] += count;
+        }
+
+        let [black, white, ..] = self.levels_value;
+        Plot::new("levels_histogram")
+            .height(150.0)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .allow_boxed_zoom(false)
+            .show(ui, |plot_ui| {
+                let bars: Vec<Bar> = self
+                    .levels_histogram
+                    .iter()
+                    .enumerate()
+                    .map(|(v, count)| Bar::new(v as f64, *count as f64).width(1.0))
+                    .collect();
+                plot_ui.bar_chart(BarChart::new(bars).color(egui::Color32::GRAY));
+                plot_ui.line(
+                    Line::new(
+                        output
+                            .iter()
+                            .enumerate()
+                            .map(|(v, count)| [v as f64, *count as f64])
+                            .collect::<Vec<_>>(),
+                    )
+                    .color(egui::Color32::LIGHT_BLUE),
+                );
+                plot_ui.vline(VLine::new(black as f64).color(egui::Color32::BLACK));
+                plot_ui.vline(VLine::new(white as f64).color(egui::Color32::WHITE));
+            });
+
+        let mut changed = false;
+        for (index, text) in ["输入黑场", "输入白场"].iter().enumerate() {
+            changed |= ui
+                .add(egui::Slider::new(&mut self.levels_value[index], 0.0..=255.0).text(*text))
+                .drag_stopped();
+        }
+        changed |= ui
+            .add(
+                egui::Slider::new(&mut self.levels_value[2], 0.1..=10.0)
+                    .logarithmic(true)
+                    .text("γ"),
+            )
+            .drag_stopped();
+        for (index, text) in [(3, "输出黑场"), (4, "输出白场")] {
+            changed |= ui
+                .add(egui::Slider::new(&mut self.levels_value[index], 0.0..=255.0).text(text))
+                .drag_stopped();
+        }
+
+        if ui.button(self.string_values[71].clone()).clicked() || changed {
+            let image = apply_luts(&self.image_data, &self.levels_lut_value(), &[]);
+            self.output_result(ctx, &image, "cache31.png");
+        }
+    }
+
+    // 白平衡增益，自动或取点得到的增益叠加色温与色调
+    fn white_balance_value(&self) -> [f32; 3] {
+        let gains = temperature_tint_gains(self.wb_temperature, self.wb_tint);
+        [0, 1, 2].map(|c| self.wb_gains[c] * gains[c])
+    }
+
+    // 白平衡
+    fn white_balance_output(&mut self, ctx: &egui::Context) {
+        let image = white_balance_image(&self.image_data, self.white_balance_value());
+        self.output_result(ctx, &image, "cache33.png");
+    }
+
+    // 在结果面板上点击中性色（灰或白）像素，以其 5×5 邻域计算白平衡
+    fn neutral_click(&mut self, ctx: &egui::Context, response: &egui::Response) {
+        if !self.pick_neutral || !response.clicked() {
+            return;
+        }
+
+        if let Some((x, y)) = self.pointer_pixel(response) {
+            self.wb_gains = neutral_point_gains(&self.image_data, (x as u32, y as u32), 2);
+            self.white_balance_output(ctx);
+        }
+    }
+
+    // 依次应用白平衡、颜色矩阵、饱和度、色相、通道混合、对比度、亮度、曲线与色阶调整，
+    // 均为逐像素的颜色变换
+    fn color_adjustments(&self, image: &image::DynamicImage) -> image::DynamicImage {
+        let image = white_balance_image(image, self.white_balance_value());
+        let image = color_matrix_image(&image, self.color_matrix);
+        let image = adjust_saturation_image(&image, self.saturation_value, self.saturation_space);
+        let image = adjust_vibrance_image(&image, self.vibrance_value, self.saturation_space);
+        let image = rotate_hue_image(&image, self.hue_value);
+        let image = channel_mixer_image(&image, self.channel_mixer, self.mixer_monochrome);
+        let image = adjust_contrast_image(&image, self.adjust_contrast_value);
+        // 亮度按当前图像的满量程换算，导出的查找表与预览一致
+        let max_level = if is_high_depth(&self.image_data) {
+            65535.0
+        } else {
+            255.0
+        };
+        let image = adjust_brightness_normalized(&image, self.brightness_value as f32 / max_level);
+        let master = self.curve_lut_of(0);
+        let channels: Vec<Vec<u8>> = (1..4).map(|c| self.curve_lut_of(c)).collect();
+        let image = apply_luts(&image, &master, &channels);
+        apply_luts(&image, &self.levels_lut_value(), &[])
+    }
+
+    // 加载 .cube 文件或 HaldCLUT 图像
+    fn load_lut3d(&mut self) {
+        let file = FileDialog::new()
+            .add_filter("3D LUT", &["cube", "png", "tif", "tiff"])
+            .set_directory("/")
+            .pick_file();
+
+        if let Some(file) = file {
+            let lut = match file.to_str() {
+                Some(path) if path.to_lowercase().ends_with(".cube") => Lut3d::load_cube(path),
+                _ => image::open(&file)
+                    .map_err(|e| format!("无法读取图像：{}", e))
+                    .and_then(|image| Lut3d::from_hald(&image)),
+            };
+
+            match lut {
+                Ok(lut) => {
+                    self.lut3d = Some(lut);
+                    self.lut_error = None;
+                }
+                Err(e) => self.lut_error = Some(format!("加载LUT失败：{}", e)),
+            }
+        }
+    }
+
+    // 3D LUT 颜色变换
+    fn lut3d_output(&mut self, ctx: &egui::Context) {
+        if let Some(lut) = &self.lut3d {
+            let image = lut3d_image(&self.image_data, lut, self.lut_interpolation);
+            self.output_result(ctx, &image, "cache32.png");
+        }
+    }
+
+    // 将当前的颜色调整导出为 .cube 文件
+    fn export_cube(&mut self) {
+        let file = FileDialog::new()
+            .add_filter("Cube LUT", &["cube"])
+            .set_directory("/")
+            .set_file_name("adjustments.cube")
+            .save_file();
+
+        if let Some(file) = file {
+            let result = file
+                .to_str()
+                .ok_or("路径包含非 UTF-8 字符".to_string())
+                .and_then(|path| {
+                    let mut lut = Lut3d::from_adjustments(self.lut_size, |image| {
+                        self.color_adjustments(image)
+                    })?;
+                    lut.title = "adjustments".to_string();
+                    lut.save_cube(path)
+                });
+
+            self.lut_error = result.err().map(|e| format!("导出LUT失败：{}", e));
+        }
+    }
+
+    // 对当前结果做伪彩色映射，便于查看频谱、距离图等灰度结果
+    fn colormap_output(&mut self, ctx: &egui::Context) {
+        let image = self.current_result();
+        self.colormap_range = if self.colormap_auto {
+            intensity_range(&image)
+        } else {
+            (0.0, 1.0)
+        };
+
+        let image = colormap_image(&image, self.colormap, Some(self.colormap_range));
+        self.output_result(ctx, &image::DynamicImage::ImageRgb8(image), "cache39.png");
+    }
+
+    // 色标，两端标注对应的灰度值（按 8 位灰度显示）
+    fn colorbar_legend(&self, ui: &mut egui::Ui) {
+        let (rect, _) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), 16.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let steps = 64;
+        for i in 0..steps {
+            let [r, g, b] = self.colormap.color(i as f32 / (steps - 1) as f32);
+            let x0 = rect.min.x + rect.width() * i as f32 / steps as f32;
+            let x1 = rect.min.x + rect.width() * (i + 1) as f32 / steps as f32;
+            painter.rect_filled(
+                egui::Rect::from_x_y_ranges(x0..=x1, rect.y_range()),
+                0.0,
+                egui::Color32::from_rgb(r, g, b),
+            );
+        }
+
+        let (low, high) = self.colormap_range;
+        ui.horizontal(|ui| {
+            ui.label(format!("{:.1}", low * 255.0));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(format!("{:.1}", high * 255.0));
+            });
+        });
+    }
+
+    // 保存竖直色标图像
+    fn save_colorbar(&mut self) {
+        let file = FileDialog::new()
+            .add_filter("PNG", &["png"])
+            .set_directory("/")
+            .set_file_name(format!("{}.png", self.colormap.name()))
+            .save_file();
+
+        if let Some(file) = file {
+            let result = colorbar_image(self.colormap, 32, 256, true).save(&file);
+            self.colormap_error = result.err().map(|e| format!("保存色标失败：{}", e));
+        }
+    }
+
+    // 当前显示的处理结果，尚未处理时为原图
+    fn current_result(&self) -> image::DynamicImage {
+        match image::open(&self.image_file_path) {
+            Ok(image) => image,
+            Err(_) => self.image_data.clone(),
+        }
+    }
+
+    // 当前选择的结构元素
+    fn structuring_element(&self) -> StructuringElement {
+        let size = self.element_size;
+        match self.element_shape {
+            0 => StructuringElement::square(size),
+            1 => StructuringElement::cross(size),
+            2 => StructuringElement::disk(size / 2),
+            _ => StructuringElement::custom(size, size, self.element_custom.clone()),
+        }
+    }
+
+    // 按列排序连通域统计表，再次点击同一列时反向
+    fn sort_components(&mut self, column: usize) {
+        if self.component_sort.0 == column {
+            self.component_sort.1 = !self.component_sort.1;
+        } else {
+            self.component_sort = (column, true);
+        }
+
+        let key = |s: &ComponentStats| -> f64 {
+            match column {
+                0 => s.label as f64,
+                1 => s.area as f64,
+                2 => s.bounding_box[0] as f64,
+                3 => s.centroid.0,
+                4 => s.centroid.1,
+                5 => s.perimeter as f64,
+                6 => s.eccentricity,
+                7 => s.orientation,
+                _ => s.mean_intensity,
+            }
+        };
+
+        let ascending = self.component_sort.1;
+        self.component_stats.sort_by(|a, b| {
+            let order = key(a).total_cmp(&key(b));
+            if ascending {
+                order
+            } else {
+                order.reverse()
+            }
+        });
+    }
+
+    // 连通域统计表
+    fn components_table(&mut self, ui: &mut egui::Ui) {
+        if ui.button(self.string_values[37].clone()).clicked() {
+            let file = FileDialog::new()
+                .add_filter("CSV", &["csv"])
+                .set_file_name("components.csv")
+                .save_file();
+
+            if let Some(file) = file {
+                let result = file
+                    .to_str()
+                    .ok_or("路径包含非 UTF-8 字符".to_string())
+                    .and_then(|path| save_stats_csv(&self.component_stats, path));
+                self.component_error = result.err().map(|e| format!("导出失败：{}", e));
+            }
+        }
+
+        if let Some(error) = &self.component_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        let headers = [
+            "标号",
+            "面积",
+            "外接矩形",
+            "质心X",
+            "质心Y",
+            "周长",
+            "离心率",
+            "方向",
+            "平均灰度",
+        ];
+
+        let mut clicked = None;
+        egui_extras::TableBuilder::new(ui)
+            .striped(true)
+            .columns(egui_extras::Column::auto().resizable(true), headers.len())
+            .header(20.0, |mut header| {
+                for (k, name) in headers.iter().enumerate() {
+                    header.col(|ui| {
+                        let mut text = name.to_string();
+                        if self.component_sort.0 == k {
+                            text += if self.component_sort.1 {
+                                " ▲"
+                            } else {
+                                " ▼"
+                            };
+                        }
+                        if ui.button(text).clicked() {
+                            clicked = Some(k);
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(18.0, self.component_stats.len(), |mut row| {
+                    let s = &self.component_stats[row.index()];
+                    let values = [
+                        s.label.to_string(),
+                        s.area.to_string(),
+                        format!("{:?}", s.bounding_box),
+                        format!("{:.2}", s.centroid.0),
+                        format!("{:.2}", s.centroid.1),
+                        s.perimeter.to_string(),
+                        format!("{:.3}", s.eccentricity),
+                        format!("{:.3}", s.orientation),
+                        format!("{:.2}", s.mean_intensity),
+                    ];
+                    for value in values {
+                        row.col(|ui| {
+                            ui.label(value);
+                        });
+                    }
+                });
+            });
+
+        if let Some(column) = clicked {
+            self.sort_components(column);
+        }
+    }
+
+    // 载入预设卷积核
+    fn set_kernel(&mut self, kernel: Kernel) {
+        self.kernel_size = kernel.size().0;
+        self.kernel_values = kernel.weights();
+    }
+
+    // 灰度线性变换，16位及浮点图像按16位处理
+    fn gray_linear_output(&mut self) {
+        let image = &self.image_data;
+        let width = image.dimensions().0;
+        let height = image.dimensions().1;
+
+        let a = self.line_transform[0];
+        let b = self.line_transform[1];
+
+        let image = if is_high_depth(image) {
+            let image_gray =
+                gray_linear_transfromationg16(image, a as f32, b as f32, self.gray_level);
+            image::DynamicImage::ImageLuma16(
+                image::ImageBuffer::from_vec(width, height, image_gray).unwrap(),
+            )
+        } else {
+            let gray_level = self.gray_level.min(255) as u8;
+            let image_gray = gray_linear_transfromationg(image, a as f32, b as f32, gray_level);
+            image::DynamicImage::ImageLuma8(
+                image::GrayImage::from_vec(width, height, image_gray).unwrap(),
+            )
+        };
+
+        if self.write_cache(&image, "cache3.png") {
+            self.image_file_path = "./cache3.png".to_string();
         }
     }
 }
@@ -106,119 +1249,851 @@ impl eframe::App for ImageApp {
                 // 打开图像
                 if ui.button(self.string_values[1].clone()).clicked() {
                     let file = FileDialog::new()
-                        .add_filter("Image Files", &["png"])
+                        .add_filter("Image Files", &["png", "tif", "tiff"])
                         .set_directory("/")
                         .pick_file();
 
-                    if let Some(file) = file {
-                        self.image_file_path = file.to_str().unwrap().to_string();
-                        self.state[0] = true;
-                        self.state[1] = true;
-                    };
-                };
+                    if let Some(file) = file {
+                        self.image_file_path = file.to_str().unwrap().to_string();
+                        self.state[0] = true;
+                        self.state[1] = true;
+                    };
+                };
+
+                ui.group(|ui| {
+                    ui.set_enabled(self.state[1]);
+
+                    // 图像灰度处理，可选择灰度化标准
+                    ui.horizontal(|ui| {
+                        if ui.button(self.string_values[3].clone()).clicked() {
+                            let image_data = &self.image_data;
+                            let width = image_data.dimensions().0;
+                            let height = image_data.dimensions().1;
+
+                            // 16位及浮点图像输出16位灰度图
+                            let image = if is_high_depth(image_data) {
+                                let image_gray =
+                                    image_to_gray16_standard(image_data, self.gray_standard);
+                                image::DynamicImage::ImageLuma16(
+                                    image::ImageBuffer::from_vec(width, height, image_gray)
+                                        .unwrap(),
+                                )
+                            } else {
+                                let image_gray =
+                                    image_to_gray_standard(image_data, self.gray_standard);
+                                image::DynamicImage::ImageLuma8(
+                                    image::GrayImage::from_vec(width, height, image_gray).unwrap(),
+                                )
+                            };
+
+                            if self.write_cache(&image, "cache1.png") {
+                                self.image_file_path = "./cache1.png".to_string();
+                            }
+                        };
+                        egui::ComboBox::from_id_source("gray_standard")
+                            .selected_text(self.gray_standard.name())
+                            .show_ui(ui, |ui| {
+                                for standard in [
+                                    GrayStandard::Bt601,
+                                    GrayStandard::Bt709,
+                                    GrayStandard::Average,
+                                    GrayStandard::Lightness,
+                                    GrayStandard::Channel(0),
+                                    GrayStandard::Channel(1),
+                                    GrayStandard::Channel(2),
+                                ] {
+                                    ui.selectable_value(
+                                        &mut self.gray_standard,
+                                        standard,
+                                        standard.name(),
+                                    );
+                                }
+                            });
+                    });
+
+                    // 直方图均衡化，彩色方式保留颜色
+                    ui.horizontal(|ui| {
+                        if ui.button(self.string_values[4].clone()).clicked() {
+                            let image = &self.image_data;
+                            let width = image.dimensions().0;
+                            let height = image.dimensions().1;
+
+                            let image = match self.equalize_mode {
+                                Some(mode) => equalize_color_image(image, mode),
+                                None if is_high_depth(image) => {
+                                    let image_gray = image_gray_average16(image);
+                                    image::DynamicImage::ImageLuma16(
+                                        image::ImageBuffer::from_vec(width, height, image_gray)
+                                            .unwrap(),
+                                    )
+                                }
+                                None => {
+                                    let image_gray = image_gray_average(image);
+                                    image::DynamicImage::ImageLuma8(
+                                        image::GrayImage::from_vec(width, height, image_gray)
+                                            .unwrap(),
+                                    )
+                                }
+                            };
+
+                            self.output_result(ctx, &image, "cache2.png");
+                        }
+
+                        egui::ComboBox::from_id_source("equalize_mode")
+                            .selected_text(equalize_mode_text(self.equalize_mode))
+                            .show_ui(ui, |ui| {
+                                for mode in [
+                                    None,
+                                    Some(EqualizeMode::Hsv),
+                                    Some(EqualizeMode::Lab),
+                                    Some(EqualizeMode::YCbCr),
+                                    Some(EqualizeMode::Rgb),
+                                ] {
+                                    ui.selectable_value(
+                                        &mut self.equalize_mode,
+                                        mode,
+                                        equalize_mode_text(mode),
+                                    );
+                                }
+                            });
+                    });
+
+                    // CLAHE，与直方图均衡化使用相同的处理方式
+                    ui.collapsing(self.string_values[56].clone(), |ui| {
+                        let mut changed = false;
+                        for (index, text) in [(0, 57), (1, 58)] {
+                            changed |= ui
+                                .add(
+                                    egui::Slider::new(&mut self.clahe_tiles[index], 1..=16)
+                                        .text(self.string_values[text].clone()),
+                                )
+                                .drag_stopped();
+                        }
+                        changed |= ui
+                            .add(
+                                egui::Slider::new(&mut self.clahe_clip, 1.0..=10.0)
+                                    .text(self.string_values[59].clone()),
+                            )
+                            .drag_stopped();
+
+                        if ui.button(self.string_values[56].clone()).clicked() || changed {
+                            self.clahe_output(ctx);
+                        }
+                    });
+
+                    // 直方图匹配，与直方图均衡化使用相同的处理方式
+                    ui.collapsing(self.string_values[60].clone(), |ui| {
+                        ui.horizontal(|ui| {
+                            if ui.button(self.string_values[61].clone()).clicked() {
+                                let file = FileDialog::new()
+                                    .add_filter("Image Files", &["png", "tif", "tiff"])
+                                    .set_directory("/")
+                                    .pick_file();
+
+                                if let Some(file) = file {
+                                    match image::open(&file) {
+                                        Ok(image) => {
+                                            self.reference_image = Some(image);
+                                            self.reference_error = None;
+                                        }
+                                        Err(e) => {
+                                            self.reference_error =
+                                                Some(format!("无法读取参考图像：{}", e));
+                                        }
+                                    }
+                                }
+                            }
+
+                            ui.add_enabled_ui(self.reference_image.is_some(), |ui| {
+                                if ui.button(self.string_values[62].clone()).clicked() {
+                                    self.histogram_matching_output(ctx, true);
+                                }
+                            });
+                        });
+
+                        if let Some(error) = &self.reference_error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
+
+                        self.distribution_editor(ui);
+
+                        ui.horizontal(|ui| {
+                            if ui.button(self.string_values[63].clone()).clicked() {
+                                self.histogram_matching_output(ctx, false);
+                            }
+
+                            if ui.button(self.string_values[64].clone()).clicked() {
+                                self.target_distribution = vec![0.5; 256];
+                            }
+                        });
+                    });
+
+                    // 强度变换，参数拖动结束后立即预览
+                    ui.collapsing(self.string_values[65].clone(), |ui| {
+                        let names = ["伽马", "对数", "反对数", "百分位拉伸", "位平面", "分段线性"];
+                        egui::ComboBox::from_id_source("transform_kind")
+                            .selected_text(names[self.transform_kind])
+                            .show_ui(ui, |ui| {
+                                for (index, name) in names.iter().enumerate() {
+                                    ui.selectable_value(&mut self.transform_kind, index, *name);
+                                }
+                            });
+
+                        let mut changed = false;
+                        ui.horizontal(|ui| match self.transform_kind {
+                            0 => {
+                                changed |= ui
+                                    .add(
+                                        egui::DragValue::new(&mut self.intensity_value[0])
+                                            .speed(0.01)
+                                            .clamp_range(0.01..=10.0)
+                                            .prefix("γ "),
+                                    )
+                                    .drag_stopped();
+                            }
+                            1 | 2 => {
+                                changed |= ui
+                                    .add(
+                                        egui::DragValue::new(&mut self.intensity_value[1])
+                                            .speed(0.1)
+                                            .clamp_range(0.1..=1000.0)
+                                            .prefix("c "),
+                                    )
+                                    .drag_stopped();
+                            }
+                            3 => {
+                                for index in [2, 3] {
+                                    changed |= ui
+                                        .add(
+                                            egui::DragValue::new(&mut self.intensity_value[index])
+                                                .speed(0.1)
+                                                .clamp_range(0.0..=100.0)
+                                                .suffix("%"),
+                                        )
+                                        .drag_stopped();
+                                }
+                            }
+                            4 => {
+                                let bits = if is_high_depth(&self.image_data) {
+                                    15
+                                } else {
+                                    7
+                                };
+                                changed |= ui
+                                    .add(egui::Slider::new(&mut self.bit_plane, 0..=bits))
+                                    .drag_stopped();
+                            }
+                            _ => {}
+                        });
+
+                        // 分段线性变换的控制点，输入与输出均为 0~1
+                        if self.transform_kind == 5 {
+                            let mut remove = None;
+                            for (index, point) in self.piecewise_points.iter_mut().enumerate() {
+                                ui.horizontal(|ui| {
+                                    for v in point.iter_mut() {
+                                        changed |= ui
+                                            .add(
+                                                egui::DragValue::new(v)
+                                                    .speed(0.005)
+                                                    .clamp_range(0.0..=1.0),
+                                            )
+                                            .drag_stopped();
+                                    }
+                                    if ui.button("×").clicked() {
+                                        remove = Some(index);
+                                    }
+                                });
+                            }
+
+                            if let Some(index) = remove {
+                                self.piecewise_points.remove(index);
+                                changed = true;
+                            }
+                            if ui.button(self.string_values[67].clone()).clicked() {
+                                self.piecewise_points.push([0.5, 0.5]);
+                            }
+                        }
+
+                        if ui.button(self.string_values[66].clone()).clicked() || changed {
+                            self.transform_output(ctx);
+                        }
+                    });
+
+                    // 曲线调整，主曲线作用于所有通道
+                    ui.collapsing(self.string_values[68].clone(), |ui| {
+                        ui.horizontal(|ui| {
+                            for (index, text) in ["主", "R", "G", "B"].iter().enumerate() {
+                                ui.selectable_value(&mut self.curve_channel, index, *text);
+                            }
+
+                            if ui.button(self.string_values[70].clone()).clicked() {
+                                self.curve_points[self.curve_channel] =
+                                    vec![[0.0, 0.0], [255.0, 255.0]];
+                                self.curves_output(ctx);
+                            }
+                        });
+
+                        self.curves_editor(ctx, ui);
+                    });
+
+                    // 色阶
+                    if ui.button(self.string_values[69].clone()).clicked() {
+                        self.levels_histogram = gray_histogram(&image_to_gray(&self.image_data));
+                        self.show_levels = true;
+                    }
+
+                    // 灰度图线性变换
+                    let max_level = if is_high_depth(&self.image_data) {
+                        65535
+                    } else {
+                        255
+                    };
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add(
+                                egui::Slider::new(
+                                    &mut self.line_transform[1],
+                                    -max_level..=max_level,
+                                )
+                                .text(self.string_values[5].clone()),
+                            )
+                            .drag_stopped()
+                        {
+                            self.gray_linear_output();
+                        };
+
+                        if ui
+                            .add(egui::DragValue::new(&mut self.line_transform[0]).speed(1))
+                            .drag_stopped()
+                        {
+                            self.gray_linear_output();
+                        }
+                    });
+
+                    // 灰度线性变换上限
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut self.gray_level)
+                                    .speed(1)
+                                    .clamp_range(0..=65535),
+                            )
+                            .drag_stopped()
+                        {
+                            self.gray_linear_output();
+                        }
+
+                        ui.monospace(self.string_values[16].clone());
+                    });
+
+                    // 灰度图傅里叶变换
+                    if ui.button(self.string_values[6].clone()).clicked() {
+                        let image = &self.image_data;
+                        let image: GrayImage = gray_fast_fourier_transform(image);
+
+                        image.save("./cache4.png").unwrap();
+                        self.image_file_path = "./cache4.png".to_string();
+                    }
+
+                    // 伪彩色，作用于当前显示的结果
+                    ui.collapsing(self.string_values[93].clone(), |ui| {
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_source("colormap")
+                                .selected_text(self.colormap.name())
+                                .show_ui(ui, |ui| {
+                                    for map in Colormap::ALL {
+                                        ui.selectable_value(&mut self.colormap, map, map.name());
+                                    }
+                                });
+
+                            ui.checkbox(&mut self.colormap_auto, self.string_values[94].clone());
+                        });
+
+                        self.colorbar_legend(ui);
+
+                        ui.horizontal(|ui| {
+                            if ui.button(self.string_values[95].clone()).clicked() {
+                                self.colormap_output(ctx);
+                            }
+
+                            if ui.button(self.string_values[96].clone()).clicked() {
+                                self.save_colorbar();
+                            }
+                        });
+
+                        if let Some(error) = &self.colormap_error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
+                    });
+
+                    // 图像分割
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add(
+                                egui::Slider::new(&mut self.segmentation_index, 0..=255)
+                                    .text(self.string_values[12].clone()),
+                            )
+                            .drag_stopped()
+                        {
+                            self.threshold_method = None;
+
+                            let image = &self.image_data;
+                            let image = segmentate_image(image, self.segmentation_index);
+
+                            self.output_result(
+                                ctx,
+                                &image::DynamicImage::ImageLuma8(image),
+                                "cache10.png",
+                            );
+                        }
+
+                        // 自动阈值
+                        let method = self.threshold_method;
+                        egui::ComboBox::from_id_source("threshold_method")
+                            .selected_text(threshold_method_text(self.threshold_method))
+                            .show_ui(ui, |ui| {
+                                for method in [
+                                    None,
+                                    Some(ThresholdMethod::Otsu),
+                                    Some(ThresholdMethod::Triangle),
+                                    Some(ThresholdMethod::Kapur),
+                                    Some(ThresholdMethod::IsoData),
+                                    Some(ThresholdMethod::Huang),
+                                ] {
+                                    ui.selectable_value(
+                                        &mut self.threshold_method,
+                                        method,
+                                        threshold_method_text(method),
+                                    );
+                                }
+                            });
+
+                        if method != self.threshold_method {
+                            if let Some(method) = self.threshold_method {
+                                let (threshold, image) = auto_threshold(&self.image_data, method);
+                                self.segmentation_index = threshold;
+
+                                self.output_result(
+                                    ctx,
+                                    &image::DynamicImage::ImageLuma8(image),
+                                    "cache10.png",
+                                );
+                            }
+                        }
+
+                        ui.monospace(self.string_values[28].clone());
+                    });
+
+                    // 多阈值 Otsu 分割
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut self.multi_otsu_classes)
+                                .speed(1)
+                                .clamp_range(2..=8),
+                        );
+
+                        if ui.button(self.string_values[29].clone()).clicked() {
+                            let (thresholds, image) =
+                                multi_otsu(&self.image_data, self.multi_otsu_classes);
+                            self.multi_otsu_thresholds = thresholds;
+
+                            self.output_result(
+                                ctx,
+                                &image::DynamicImage::ImageLuma8(image),
+                                "cache17.png",
+                            );
+                        }
+
+                        if !self.multi_otsu_thresholds.is_empty() {
+                            ui.label(format!("{:?}", self.multi_otsu_thresholds));
+                        }
+                    });
+
+                    // 局部阈值分割
+                    ui.collapsing(self.string_values[30].clone(), |ui| {
+                        let method = self.local_threshold_method;
+                        egui::ComboBox::from_id_source("local_threshold_method")
+                            .selected_text(format!("{:?}", self.local_threshold_method))
+                            .show_ui(ui, |ui| {
+                                for method in [
+                                    LocalThresholdMethod::Mean,
+                                    LocalThresholdMethod::Gaussian,
+                                    LocalThresholdMethod::Niblack,
+                                    LocalThresholdMethod::Sauvola,
+                                    LocalThresholdMethod::Bernsen,
+                                ] {
+                                    ui.selectable_value(
+                                        &mut self.local_threshold_method,
+                                        method,
+                                        format!("{:?}", method),
+                                    );
+                                }
+                            });
+
+                        if method != self.local_threshold_method {
+                            self.local_threshold_value[1] = self.local_threshold_method.default_k();
+                        }
+
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut self.local_threshold_value[0])
+                                    .speed(2)
+                                    .clamp_range(3.0..=501.0),
+                            );
+                            ui.monospace(self.string_values[31].clone());
+                            ui.add(
+                                egui::DragValue::new(&mut self.local_threshold_value[1])
+                                    .speed(0.01)
+                                    .prefix("k "),
+                            );
+                        });
+
+                        if ui.button(self.string_values[30].clone()).clicked() {
+                            let image = adaptive_threshold(
+                                &self.image_data,
+                                self.local_threshold_method,
+                                self.local_threshold_value[0] as u32,
+                                self.local_threshold_value[1],
+                            );
+
+                            self.output_result(
+                                ctx,
+                                &image::DynamicImage::ImageLuma8(image),
+                                "cache18.png",
+                            );
+                        }
+                    });
+
+                    // 形态学处理，作用于当前结果以便清理分割后的二值图像
+                    ui.collapsing(self.string_values[32].clone(), |ui| {
+                        egui::ComboBox::from_id_source("morphology_operation")
+                            .selected_text(format!("{:?}", self.morphology_operation))
+                            .show_ui(ui, |ui| {
+                                for operation in [
+                                    MorphologyOperation::Erode,
+                                    MorphologyOperation::Dilate,
+                                    MorphologyOperation::Open,
+                                    MorphologyOperation::Close,
+                                    MorphologyOperation::Gradient,
+                                    MorphologyOperation::TopHat,
+                                    MorphologyOperation::BlackHat,
+                                ] {
+                                    ui.selectable_value(
+                                        &mut self.morphology_operation,
+                                        operation,
+                                        format!("{:?}", operation),
+                                    );
+                                }
+                            });
 
-                ui.group(|ui| {
-                    ui.set_enabled(self.state[1]);
+                        ui.horizontal(|ui| {
+                            let shapes = ["正方形", "十字形", "圆盘", "自定义"];
+                            egui::ComboBox::from_id_source("element_shape")
+                                .selected_text(shapes[self.element_shape])
+                                .show_ui(ui, |ui| {
+                                    for (k, shape) in shapes.iter().enumerate() {
+                                        ui.selectable_value(&mut self.element_shape, k, *shape);
+                                    }
+                                });
 
-                    // 图像灰度处理
-                    if ui.button(self.string_values[3].clone()).clicked() {
-                        let image_data = &self.image_data;
-                        let width = image_data.dimensions().0;
-                        let height = image_data.dimensions().1;
-                        let image_gray = image_to_gray(image_data);
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut self.element_size)
+                                        .speed(2)
+                                        .clamp_range(1..=51),
+                                )
+                                .changed()
+                            {
+                                self.element_size |= 1;
+                                self.element_custom =
+                                    vec![true; self.element_size * self.element_size];
+                            }
 
-                        let image = image::DynamicImage::ImageLuma8(
-                            image::GrayImage::from_vec(width, height, image_gray).unwrap(),
-                        );
+                            ui.monospace(self.string_values[33].clone());
+                        });
 
-                        image_output(&image, "cache1.png");
-                        self.image_file_path = "./cache1.png".to_string();
-                    };
+                        // 自定义结构元素编辑
+                        if self.element_shape == 3 {
+                            egui::Grid::new("element_grid").show(ui, |ui| {
+                                for row in 0..self.element_size {
+                                    for col in 0..self.element_size {
+                                        ui.checkbox(
+                                            &mut self.element_custom[row * self.element_size + col],
+                                            "",
+                                        );
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                        }
 
-                    // 灰度图均值化
-                    if ui.button(self.string_values[4].clone()).clicked() {
-                        let image = &self.image_data;
-                        let image_gray = image_gray_average(image);
-                        let width = image.dimensions().0;
-                        let height = image.dimensions().1;
+                        if ui.button(self.string_values[34].clone()).clicked() {
+                            let image = morphology(
+                                &self.current_result(),
+                                self.morphology_operation,
+                                &self.structuring_element(),
+                                self.border_mode,
+                            );
 
-                        let image = image::DynamicImage::ImageLuma8(
-                            image::GrayImage::from_vec(width, height, image_gray).unwrap(),
-                        );
+                            self.output_result(
+                                ctx,
+                                &image::DynamicImage::ImageLuma8(image),
+                                "cache19.png",
+                            );
+                        }
+                    });
 
-                        image_output(&image, "cache2.png");
-                        self.image_file_path = "./cache2.png".to_string();
-                    }
+                    // 连通域标记，作用于当前的二值结果
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut self.connectivity, Connectivity::Four, "4连通");
+                        ui.selectable_value(&mut self.connectivity, Connectivity::Eight, "8连通");
 
-                    // 灰度图线性变换
+                        if ui.button(self.string_values[35].clone()).clicked() {
+                            let binary = self.current_result().to_luma8();
+                            let labels = label_components(&binary, self.connectivity);
+                            self.component_stats = component_stats(&labels, &self.image_data);
+                            self.component_sort = (0, true);
+                            self.show_components = true;
+
+                            let image = colorize_labels(&labels);
+                            self.output_result(
+                                ctx,
+                                &image::DynamicImage::ImageRgb8(image),
+                                "cache20.png",
+                            );
+                        }
+                    });
+
+                    // 轮廓提取，作用于当前的二值结果
                     ui.horizontal(|ui| {
                         if ui
                             .add(
-                                egui::Slider::new(&mut self.line_transform[1], -255..=255)
-                                    .text(self.string_values[5].clone()),
+                                egui::DragValue::new(&mut self.contour_epsilon)
+                                    .speed(0.1)
+                                    .clamp_range(0.0..=20.0),
                             )
                             .drag_stopped()
+                            && !self.contours.is_empty()
                         {
-                            let image = &self.image_data;
-                            let width = image.dimensions().0;
-                            let height = image.dimensions().1;
+                            self.contour_output(ctx);
+                        }
+                        ui.monospace(self.string_values[39].clone());
 
-                            let a = self.line_transform[0];
-                            let b = self.line_transform[1];
+                        egui::ComboBox::from_id_source("contour_shape")
+                            .selected_text(
+                                ["轮廓", "凸包", "最小外接矩形", "最小外接圆"][self.contour_shape],
+                            )
+                            .show_ui(ui, |ui| {
+                                for (index, text) in ["轮廓", "凸包", "最小外接矩形", "最小外接圆"]
+                                    .iter()
+                                    .enumerate()
+                                {
+                                    ui.selectable_value(&mut self.contour_shape, index, *text);
+                                }
+                            });
 
-                            let image_gray =
-                                gray_linear_transfromationg(image, a as f32, b as f32, 255);
-                            let image = image::DynamicImage::ImageLuma8(
-                                image::GrayImage::from_vec(width, height, image_gray).unwrap(),
-                            );
+                        if ui.button(self.string_values[38].clone()).clicked() {
+                            self.contour_output(ctx);
+                        }
 
-                            image_output(&image, "cache3.png");
-                            self.image_file_path = "./cache3.png".to_string();
-                        };
+                        if ui.button(self.string_values[40].clone()).clicked()
+                            && !self.contours.is_empty()
+                        {
+                            let file = FileDialog::new()
+                                .add_filter("SVG", &["svg"])
+                                .add_filter("GeoJSON", &["geojson", "json"])
+                                .set_file_name("contours.svg")
+                                .save_file();
+
+                            if let Some(file) = file {
+                                let (width, height) = self.image_data.dimensions();
+                                let result = file
+                                    .to_str()
+                                    .ok_or("路径包含非 UTF-8 字符".to_string())
+                                    .and_then(|path| {
+                                        save_contours(&self.contours, width, height, path)
+                                    });
+                                self.contour_error =
+                                    result.err().map(|e| format!("导出失败：{}", e));
+                            }
+                        }
+                    });
+
+                    if let Some(error) = &self.contour_error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+
+                    // 分水岭分割，梯度模式作用于原图，距离变换模式作用于当前的二值结果
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut self.watershed_source, 0, "梯度");
+                        ui.selectable_value(&mut self.watershed_source, 1, "距离变换");
+
+                        ui.add(
+                            egui::DragValue::new(&mut self.watershed_depth)
+                                .speed(0.1)
+                                .clamp_range(0.0..=255.0),
+                        );
+                        ui.monospace(self.string_values[42].clone());
+
+                        if ui.button(self.string_values[41].clone()).clicked() {
+                            self.watershed_output(ctx);
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .checkbox(&mut self.paint_markers, self.string_values[43].clone())
+                            .changed()
+                            && self.paint_markers
+                        {
+                            self.magic_wand = false;
+                            self.pick_neutral = false;
+                        }
+                        ui.checkbox(&mut self.watershed_labels, self.string_values[45].clone());
 
+                        if ui.button(self.string_values[44].clone()).clicked() {
+                            self.marker_strokes.clear();
+                        }
+                    });
+
+                    // 魔棒选区，在结果面板上点击选取，连通方式与连通域标记相同
+                    ui.horizontal(|ui| {
                         if ui
-                            .add(egui::DragValue::new(&mut self.line_transform[0]).speed(1))
-                            .drag_stopped()
+                            .checkbox(&mut self.magic_wand, self.string_values[50].clone())
+                            .changed()
+                            && self.magic_wand
                         {
-                            let image = &self.image_data;
-                            let width = image.dimensions().0;
-                            let height = image.dimensions().1;
+                            self.paint_markers = false;
+                            self.pick_neutral = false;
+                        }
 
-                            let a = self.line_transform[0];
-                            let b = self.line_transform[1];
+                        ui.add(
+                            egui::DragValue::new(&mut self.wand_tolerance)
+                                .speed(0.5)
+                                .clamp_range(0.0..=255.0),
+                        );
+                        ui.monospace(self.string_values[51].clone());
 
-                            let image_gray =
-                                gray_linear_transfromationg(image, a as f32, b as f32, 255);
-                            let image = image::DynamicImage::ImageLuma8(
-                                image::GrayImage::from_vec(width, height, image_gray).unwrap(),
-                            );
+                        // 输出二值掩模，可继续用于轮廓提取等
+                        if ui.button(self.string_values[52].clone()).clicked()
+                            && self.selection.width() > 0
+                        {
+                            let mask = image::DynamicImage::ImageLuma8(self.selection.clone());
+                            self.output_result(ctx, &mask, "cache25.png");
+                        }
 
-                            image_output(&image, "cache3.png");
-                            self.image_file_path = "./cache3.png".to_string();
+                        if ui.button(self.string_values[53].clone()).clicked() {
+                            self.selection = GrayImage::new(0, 0);
                         }
                     });
 
-                    // 灰度图傅里叶变换
-                    if ui.button(self.string_values[6].clone()).clicked() {
-                        let image = &self.image_data;
-                        let image: GrayImage = gray_fast_fourier_transform(image);
+                    // 颜色空间通道以灰度图显示
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_source("color_space")
+                            .selected_text(self.color_space.name())
+                            .show_ui(ui, |ui| {
+                                for space in ColorSpace::ALL {
+                                    if ui
+                                        .selectable_value(
+                                            &mut self.color_space,
+                                            space,
+                                            space.name(),
+                                        )
+                                        .changed()
+                                    {
+                                        self.color_channel = 0;
+                                    }
+                                }
+                            });
+                        ui.monospace(self.string_values[54].clone());
 
-                        image.save("./cache4.png").unwrap();
-                        self.image_file_path = "./cache4.png".to_string();
-                    }
+                        for (index, name) in self.color_space.channel_names().iter().enumerate() {
+                            ui.selectable_value(&mut self.color_channel, index, *name);
+                        }
 
-                    // 图像分割
-                    if ui
-                        .add(
-                            egui::Slider::new(&mut self.segmentation_index, 0..=255)
-                                .text(self.string_values[12].clone()),
-                        )
-                        .drag_stopped()
-                    {
-                        let image = &self.image_data;
-                        let image = segmentate_image(image, self.segmentation_index);
+                        if ui.button(self.string_values[55].clone()).clicked() {
+                            let image = channel_image(
+                                &self.image_data,
+                                self.color_space,
+                                self.color_channel,
+                            );
+                            self.output_result(
+                                ctx,
+                                &image::DynamicImage::ImageLuma8(image),
+                                "cache26.png",
+                            );
+                        }
+                    });
+
+                    // 颜色聚类分割
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut self.cluster_space, ClusterSpace::Rgb, "RGB");
+                        ui.selectable_value(&mut self.cluster_space, ClusterSpace::Lab, "Lab");
+
+                        ui.add(egui::DragValue::new(&mut self.cluster_seed));
+                        ui.monospace(self.string_values[47].clone());
+
+                        ui.checkbox(&mut self.cluster_labels, self.string_values[45].clone());
+                    });
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add(
+                                egui::Slider::new(&mut self.cluster_k, 2..=16)
+                                    .text(self.string_values[46].clone()),
+                            )
+                            .drag_stopped()
+                        {
+                            self.kmeans_output(ctx);
+                        }
+
+                        if ui.button(self.string_values[46].clone()).clicked() {
+                            self.kmeans_output(ctx);
+                        }
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut self.mean_shift_value[0])
+                                .speed(0.1)
+                                .clamp_range(1.0..=50.0),
+                        );
+                        ui.add(
+                            egui::DragValue::new(&mut self.mean_shift_value[1])
+                                .speed(0.1)
+                                .clamp_range(1.0..=100.0),
+                        );
+                        ui.monospace(self.string_values[49].clone());
+
+                        if ui.button(self.string_values[48].clone()).clicked() {
+                            let [spatial, range] = self.mean_shift_value;
+                            let result =
+                                mean_shift(&self.image_data, spatial, range, self.cluster_space);
+                            self.cluster_output(ctx, result);
+                        }
+                    });
 
-                        image.save("./cache10.png").unwrap();
+                    // 聚类中心颜色，类别过多时不显示
+                    if !self.cluster_centers.is_empty() && self.cluster_centers.len() <= 32 {
+                        ui.horizontal_wrapped(|ui| {
+                            for center in &self.cluster_centers {
+                                let color = egui::Color32::from_rgb(
+                                    center[0].round() as u8,
+                                    center[1].round() as u8,
+                                    center[2].round() as u8,
+                                );
+                                let (rect, response) = ui.allocate_exact_size(
+                                    egui::vec2(16.0, 16.0),
+                                    egui::Sense::hover(),
+                                );
+                                ui.painter().rect_filled(rect, 2.0, color);
+                                response.on_hover_text(format!(
+                                    "({:.0}, {:.0}, {:.0})",
+                                    center[0], center[1], center[2]
+                                ));
+                            }
+                        });
                     }
 
                     // 图像对比度变换
@@ -230,27 +2105,279 @@ impl eframe::App for ImageApp {
                             let image = &self.image_data;
                             let image = adjust_contrast_image(image, self.adjust_contrast_value);
 
-                            image_output(&image, "cache5.png");
+                            self.write_cache(&image, "cache5.png");
                         }
 
                         ui.monospace(self.string_values[7].clone());
                     });
 
-                    // 图像亮度变换
+                    // 图像亮度变换，增量范围与灰度线性变换相同，按原图位深计算
                     ui.horizontal(|ui| {
                         if ui
-                            .add(egui::DragValue::new(&mut self.brightness_value).speed(1))
+                            .add(
+                                egui::DragValue::new(&mut self.brightness_value)
+                                    .speed(max_level as f64 / 255.0)
+                                    .clamp_range(-max_level..=max_level),
+                            )
                             .drag_stopped()
                         {
                             let image = &self.image_data;
                             let image = adjust_brightness_image(image, self.brightness_value);
 
-                            image_output(&image, "cache6.png");
+                            self.write_cache(&image, "cache6.png");
                         }
 
                         ui.monospace(self.string_values[8].clone());
                     });
 
+                    // 饱和度与自然饱和度按 HSV 或 HSL 计算
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut self.saturation_value)
+                                    .speed(0.01)
+                                    .clamp_range(0.0..=4.0),
+                            )
+                            .drag_stopped()
+                        {
+                            let image = adjust_saturation_image(
+                                &self.image_data,
+                                self.saturation_value,
+                                self.saturation_space,
+                            );
+
+                            self.output_result(ctx, &image, "cache35.png");
+                        }
+
+                        ui.monospace(self.string_values[87].clone());
+                        ui.selectable_value(&mut self.saturation_space, ColorSpace::Hsl, "HSL");
+                        ui.selectable_value(&mut self.saturation_space, ColorSpace::Hsv, "HSV");
+                    });
+
+                    // 自然饱和度
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut self.vibrance_value)
+                                    .speed(0.01)
+                                    .clamp_range(-1.0..=1.0),
+                            )
+                            .drag_stopped()
+                        {
+                            let image = adjust_vibrance_image(
+                                &self.image_data,
+                                self.vibrance_value,
+                                self.saturation_space,
+                            );
+
+                            self.output_result(ctx, &image, "cache36.png");
+                        }
+
+                        ui.monospace(self.string_values[89].clone());
+                    });
+
+                    // 色相旋转
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut self.hue_value)
+                                    .speed(1.0)
+                                    .clamp_range(-180.0..=180.0)
+                                    .suffix("°"),
+                            )
+                            .drag_stopped()
+                        {
+                            let image = rotate_hue_image(&self.image_data, self.hue_value);
+
+                            self.output_result(ctx, &image, "cache37.png");
+                        }
+
+                        ui.monospace(self.string_values[88].clone());
+                    });
+
+                    // 通道混合器，每行为输出通道 R、G、B 对输入 R、G、B 的权重
+                    ui.collapsing(self.string_values[90].clone(), |ui| {
+                        let mut changed = false;
+                        egui::Grid::new("channel_mixer").show(ui, |ui| {
+                            for (row, text) in self.channel_mixer.iter_mut().zip(["R", "G", "B"]) {
+                                ui.label(text);
+                                for value in row.iter_mut() {
+                                    changed |= ui
+                                        .add(
+                                            egui::DragValue::new(value)
+                                                .speed(0.01)
+                                                .clamp_range(-2.0..=2.0),
+                                        )
+                                        .drag_stopped();
+                                }
+                                ui.end_row();
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            changed |= ui
+                                .checkbox(
+                                    &mut self.mixer_monochrome,
+                                    self.string_values[91].clone(),
+                                )
+                                .changed();
+
+                            if ui.button(self.string_values[92].clone()).clicked() {
+                                self.channel_mixer =
+                                    [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+                                self.mixer_monochrome = false;
+                                changed = true;
+                            }
+                        });
+
+                        if changed {
+                            let image = channel_mixer_image(
+                                &self.image_data,
+                                self.channel_mixer,
+                                self.mixer_monochrome,
+                            );
+
+                            self.output_result(ctx, &image, "cache38.png");
+                        }
+                    });
+
+                    // 白平衡，均在线性光空间中按通道增益校正
+                    ui.collapsing(self.string_values[76].clone(), |ui| {
+                        ui.horizontal(|ui| {
+                            let mut method = None;
+                            if ui.button(self.string_values[77].clone()).clicked() {
+                                method = Some(WhiteBalance::GrayWorld);
+                            }
+                            if ui.button(self.string_values[78].clone()).clicked() {
+                                method = Some(WhiteBalance::WhitePatch);
+                            }
+                            if ui.button(self.string_values[79].clone()).clicked() {
+                                method = Some(WhiteBalance::Percentile(self.wb_percentile));
+                            }
+                            ui.add(
+                                egui::DragValue::new(&mut self.wb_percentile)
+                                    .speed(0.1)
+                                    .clamp_range(50.0..=100.0)
+                                    .suffix("%"),
+                            );
+
+                            if let Some(method) = method {
+                                self.wb_gains = white_balance_gains(&self.image_data, method);
+                                self.white_balance_output(ctx);
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            if ui
+                                .checkbox(&mut self.pick_neutral, self.string_values[80].clone())
+                                .changed()
+                                && self.pick_neutral
+                            {
+                                self.paint_markers = false;
+                                self.magic_wand = false;
+                            }
+
+                            if ui.button(self.string_values[83].clone()).clicked() {
+                                self.wb_gains = [1.0; 3];
+                                self.wb_temperature = 6500.0;
+                                self.wb_tint = 0.0;
+                                self.white_balance_output(ctx);
+                            }
+                        });
+
+                        let mut changed = ui
+                            .add(
+                                egui::Slider::new(&mut self.wb_temperature, 2000.0..=12000.0)
+                                    .suffix("K")
+                                    .text(self.string_values[81].clone()),
+                            )
+                            .drag_stopped();
+                        changed |= ui
+                            .add(
+                                egui::Slider::new(&mut self.wb_tint, -100.0..=100.0)
+                                    .text(self.string_values[82].clone()),
+                            )
+                            .drag_stopped();
+                        if changed {
+                            self.white_balance_output(ctx);
+                        }
+
+                        let [r, g, b] = self.white_balance_value();
+                        ui.label(format!("R {:.3}  G {:.3}  B {:.3}", r, g, b));
+                    });
+
+                    // 3×3 颜色矩阵，每行为输出通道，在线性光空间中计算
+                    ui.collapsing(self.string_values[84].clone(), |ui| {
+                        egui::Grid::new("color_matrix").show(ui, |ui| {
+                            for row in self.color_matrix.iter_mut() {
+                                for value in row.iter_mut() {
+                                    ui.add(egui::DragValue::new(value).speed(0.01));
+                                }
+                                ui.end_row();
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            if ui.button(self.string_values[85].clone()).clicked() {
+                                let image = color_matrix_image(&self.image_data, self.color_matrix);
+                                self.output_result(ctx, &image, "cache34.png");
+                            }
+
+                            if ui.button(self.string_values[86].clone()).clicked() {
+                                self.color_matrix =
+                                    [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+                            }
+                        });
+                    });
+
+                    // 3D LUT，支持 .cube 与 HaldCLUT
+                    ui.collapsing(self.string_values[72].clone(), |ui| {
+                        ui.horizontal(|ui| {
+                            if ui.button(self.string_values[73].clone()).clicked() {
+                                self.load_lut3d();
+                            }
+
+                            if let Some(lut) = &self.lut3d {
+                                ui.label(format!("{} ({}³)", lut.title, lut.size));
+                            }
+                        });
+
+                        if let Some(error) = &self.lut_error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
+
+                        ui.horizontal(|ui| {
+                            let mut changed = false;
+                            for (value, text) in [
+                                (LutInterpolation::Trilinear, "三线性"),
+                                (LutInterpolation::Tetrahedral, "四面体"),
+                            ] {
+                                changed |= ui
+                                    .selectable_value(&mut self.lut_interpolation, value, text)
+                                    .clicked();
+                            }
+
+                            ui.add_enabled_ui(self.lut3d.is_some(), |ui| {
+                                if ui.button(self.string_values[74].clone()).clicked() || changed {
+                                    self.lut3d_output(ctx);
+                                }
+                            });
+                        });
+
+                        // 白平衡、颜色矩阵、饱和度、色相、通道混合、对比度、亮度、曲线与色阶合成为一个查找表
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut self.lut_size)
+                                    .clamp_range(2..=65)
+                                    .prefix("size: "),
+                            );
+
+                            if ui.button(self.string_values[75].clone()).clicked() {
+                                self.export_cube();
+                            }
+                        });
+                    });
+
                     // 图像高斯模糊
                     ui.horizontal(|ui| {
                         if ui
@@ -260,28 +2387,308 @@ impl eframe::App for ImageApp {
                             let image = &self.image_data;
                             let image = blur_image(image, self.blur_value);
 
-                            image_output(&image, "cache7.png");
+                            self.write_cache(&image, "cache7.png");
                         }
 
                         ui.monospace(self.string_values[9].clone());
                     });
 
+                    // 邻域运算边界处理方式
+                    egui::ComboBox::from_label(self.string_values[14].clone())
+                        .selected_text(border_mode_text(self.border_mode))
+                        .show_ui(ui, |ui| {
+                            for mode in [
+                                BorderMode::Constant(0),
+                                BorderMode::Replicate,
+                                BorderMode::Reflect,
+                                BorderMode::Wrap,
+                            ] {
+                                ui.selectable_value(
+                                    &mut self.border_mode,
+                                    mode,
+                                    border_mode_text(mode),
+                                );
+                            }
+                        });
+
+                    ui.checkbox(&mut self.process_alpha, self.string_values[15].clone());
+
                     // 图像平滑
-                    if ui.button(self.string_values[10].clone()).clicked() {
-                        let image = &self.image_data;
-                        let image = smooth_image(image, 1);
+                    ui.horizontal(|ui| {
+                        if ui.button(self.string_values[10].clone()).clicked() {
+                            let image = &self.image_data;
+                            let image = smooth_image(
+                                image,
+                                self.smooth_radius,
+                                self.border_mode,
+                                self.process_alpha,
+                            );
 
-                        image_output(&image, "cache8.png");
-                    }
+                            self.write_cache(&image, "cache8.png");
+                        }
+
+                        ui.add(
+                            egui::DragValue::new(&mut self.smooth_radius)
+                                .speed(1)
+                                .clamp_range(1..=50),
+                        );
+                        ui.monospace(self.string_values[13].clone());
+                    });
 
                     // 图像锐化
                     if ui.button(self.string_values[11].clone()).clicked() {
                         let image = &self.image_data;
-                        let image = sharpen_image(image, 1);
+                        let image = sharpen_image(image, 1, self.border_mode, self.process_alpha);
 
-                        image_output(&image, "cache9.png");
+                        self.write_cache(&image, "cache9.png");
                     }
 
+                    // 反锐化掩模，数值变化时实时预览
+                    ui.horizontal(|ui| {
+                        let mut changed = false;
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.unsharp_value[0])
+                                    .speed(0.1)
+                                    .clamp_range(0.1..=50.0)
+                                    .prefix("σ "),
+                            )
+                            .changed();
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.unsharp_value[1])
+                                    .speed(0.05)
+                                    .clamp_range(0.0..=10.0)
+                                    .prefix("×"),
+                            )
+                            .changed();
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.unsharp_value[2])
+                                    .speed(1)
+                                    .clamp_range(0.0..=255.0),
+                            )
+                            .changed();
+
+                        if changed {
+                            self.unsharp_output(ctx);
+                        }
+
+                        ui.monospace(self.string_values[26].clone());
+                    });
+
+                    // 高提升滤波
+                    ui.horizontal(|ui| {
+                        let mut changed = false;
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.high_boost_value[0])
+                                    .speed(0.1)
+                                    .clamp_range(0.1..=50.0)
+                                    .prefix("σ "),
+                            )
+                            .changed();
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.high_boost_value[1])
+                                    .speed(0.05)
+                                    .clamp_range(1.0..=10.0)
+                                    .prefix("A "),
+                            )
+                            .changed();
+
+                        if changed {
+                            self.high_boost_output(ctx);
+                        }
+
+                        ui.monospace(self.string_values[27].clone());
+                    });
+
+                    // 卷积核编辑
+                    ui.collapsing(self.string_values[17].clone(), |ui| {
+                        ui.horizontal(|ui| {
+                            for size in [3, 5, 7] {
+                                if ui
+                                    .selectable_label(
+                                        self.kernel_size == size,
+                                        format!("{0}x{0}", size),
+                                    )
+                                    .clicked()
+                                    && self.kernel_size != size
+                                {
+                                    self.set_kernel(Kernel::box_filter(size));
+                                }
+                            }
+                        });
+
+                        // 预设卷积核
+                        ui.horizontal_wrapped(|ui| {
+                            let size = self.kernel_size;
+                            let presets = [
+                                ("均值", Kernel::box_filter(size)),
+                                ("高斯", Kernel::gaussian(size, 0.0)),
+                                ("拉普拉斯4", Kernel::laplacian4()),
+                                ("拉普拉斯8", Kernel::laplacian8()),
+                                ("浮雕", Kernel::emboss()),
+                                ("Prewitt X", Kernel::prewitt_x()),
+                                ("Prewitt Y", Kernel::prewitt_y()),
+                                ("Sobel X", Kernel::sobel_x()),
+                                ("Sobel Y", Kernel::sobel_y()),
+                            ];
+                            for (name, preset) in presets {
+                                if ui.button(name).clicked() {
+                                    self.set_kernel(preset);
+                                }
+                            }
+                        });
+
+                        egui::Grid::new("kernel_grid").show(ui, |ui| {
+                            for row in 0..self.kernel_size {
+                                for col in 0..self.kernel_size {
+                                    let value =
+                                        &mut self.kernel_values[row * self.kernel_size + col];
+                                    ui.add(egui::DragValue::new(value).speed(0.1).max_decimals(3));
+                                }
+                                ui.end_row();
+                            }
+                        });
+
+                        ui.checkbox(&mut self.kernel_normalize, self.string_values[18].clone());
+
+                        if ui.button(self.string_values[19].clone()).clicked() {
+                            let kernel = Kernel::new(
+                                self.kernel_size,
+                                self.kernel_size,
+                                self.kernel_values.clone(),
+                            );
+                            let image = convolve(
+                                &self.image_data,
+                                &kernel,
+                                self.kernel_normalize,
+                                self.border_mode,
+                            );
+
+                            self.output_result(ctx, &image, "cache11.png");
+                        }
+                    });
+
+                    // 边缘检测
+                    ui.collapsing(self.string_values[20].clone(), |ui| {
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_source("edge_operator")
+                                .selected_text(format!("{:?}", self.edge_operator))
+                                .show_ui(ui, |ui| {
+                                    for operator in [
+                                        GradientOperator::Sobel,
+                                        GradientOperator::Prewitt,
+                                        GradientOperator::Scharr,
+                                        GradientOperator::Roberts,
+                                    ] {
+                                        ui.selectable_value(
+                                            &mut self.edge_operator,
+                                            operator,
+                                            format!("{:?}", operator),
+                                        );
+                                    }
+                                });
+
+                            if ui.button(self.string_values[21].clone()).clicked() {
+                                let image = gradient_magnitude_image(
+                                    &self.image_data,
+                                    self.edge_operator,
+                                    self.border_mode,
+                                );
+
+                                self.output_result(
+                                    ctx,
+                                    &image::DynamicImage::ImageLuma8(image),
+                                    "cache12.png",
+                                );
+                            }
+
+                            if ui.button(self.string_values[22].clone()).clicked() {
+                                let image = gradient_direction_image(
+                                    &self.image_data,
+                                    self.edge_operator,
+                                    self.border_mode,
+                                );
+
+                                self.output_result(
+                                    ctx,
+                                    &image::DynamicImage::ImageLuma8(image),
+                                    "cache12.png",
+                                );
+                            }
+                        });
+
+                        // LoG 过零点
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut self.log_value[0])
+                                    .speed(0.1)
+                                    .clamp_range(0.5..=10.0)
+                                    .prefix("σ "),
+                            );
+                            ui.add(
+                                egui::DragValue::new(&mut self.log_value[1])
+                                    .speed(0.1)
+                                    .clamp_range(0.0..=100.0),
+                            );
+
+                            if ui.button(self.string_values[23].clone()).clicked() {
+                                let image = log_edges(
+                                    &self.image_data,
+                                    self.log_value[0],
+                                    self.log_value[1],
+                                    self.border_mode,
+                                );
+
+                                self.output_result(
+                                    ctx,
+                                    &image::DynamicImage::ImageLuma8(image),
+                                    "cache13.png",
+                                );
+                            }
+                        });
+
+                        // Canny
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut self.canny_value[0])
+                                        .speed(0.1)
+                                        .clamp_range(0.0..=10.0)
+                                        .prefix("σ "),
+                                )
+                                .drag_stopped()
+                            {
+                                self.canny_output(ctx);
+                            }
+
+                            ui.monospace("Canny");
+                        });
+
+                        if ui
+                            .add(
+                                egui::Slider::new(&mut self.canny_value[1], 0.0..=1000.0)
+                                    .text(self.string_values[24].clone()),
+                            )
+                            .drag_stopped()
+                        {
+                            self.canny_output(ctx);
+                        }
+
+                        if ui
+                            .add(
+                                egui::Slider::new(&mut self.canny_value[2], 0.0..=1000.0)
+                                    .text(self.string_values[25].clone()),
+                            )
+                            .drag_stopped()
+                        {
+                            self.canny_output(ctx);
+                        }
+                    });
+
                     // 测试
                     if ui.button("测试").clicked() {
                         println!("hello world");
@@ -290,6 +2697,25 @@ impl eframe::App for ImageApp {
             });
         });
 
+        // 连通域统计窗口
+        let mut show_components = self.show_components;
+        egui::Window::new(self.string_values[36].clone())
+            .open(&mut show_components)
+            .default_height(400.0)
+            .show(ctx, |ui| {
+                self.components_table(ui);
+            });
+        self.show_components = show_components;
+
+        // 色阶窗口
+        let mut show_levels = self.show_levels;
+        egui::Window::new(self.string_values[69].clone())
+            .open(&mut show_levels)
+            .show(ctx, |ui| {
+                self.levels_dialog(ctx, ui);
+            });
+        self.show_levels = show_levels;
+
         egui::CentralPanel::default().show(ctx, |_ui| {
             egui::TopBottomPanel::new(egui::containers::panel::TopBottomSide::Top, "top_panel")
                 .show(ctx, |ui| {
@@ -301,16 +2727,52 @@ impl eframe::App for ImageApp {
             egui::CentralPanel::default().show(ctx, |ui| {
                 ui.vertical_centered_justified(|ui| {
                     egui::ScrollArea::vertical().show(ui, |ui| {
+                        if let Some(error) = &self.output_error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
+
                         if self.image_file_path.is_empty() {
                             ui.label("请先选择图片");
                         } else {
-                            ui.image(format!("file://{}", self.image_file_path));
-
                             if self.state[0] {
                                 let image = reading_image(&self.image_file_path);
                                 self.image_data = image;
                                 self.state[0] = false;
+
+                                // egui 的图片加载器只支持 PNG，其他格式的原图由图像数据生成纹理显示
+                                self.preview_texture =
+                                    if self.image_file_path.to_lowercase().ends_with(".png") {
+                                        None
+                                    } else {
+                                        let rgba = self.image_data.to_rgba8();
+                                        let size = [rgba.width() as usize, rgba.height() as usize];
+                                        Some(ui.ctx().load_texture(
+                                            "preview",
+                                            egui::ColorImage::from_rgba_unmultiplied(size, &rgba),
+                                            Default::default(),
+                                        ))
+                                    };
+                            };
+
+                            // 手绘标记、魔棒选区或取中性色时结果图像响应鼠标
+                            let sense =
+                                if self.paint_markers || self.magic_wand || self.pick_neutral {
+                                    egui::Sense::click_and_drag()
+                                } else {
+                                    egui::Sense::hover()
+                                };
+                            let image = match &self.preview_texture {
+                                Some(texture)
+                                    if !self.image_file_path.to_lowercase().ends_with(".png") =>
+                                {
+                                    egui::Image::new(texture)
+                                }
+                                _ => egui::Image::new(format!("file://{}", self.image_file_path)),
                             };
+                            let response = ui.add(image.sense(sense));
+                            self.paint_strokes(ui, &response);
+                            self.magic_wand_click(ui.ctx(), &response);
+                            self.neutral_click(ui.ctx(), &response);
                         };
                     });
                 });
@@ -0,0 +1,316 @@
+use std::collections::VecDeque;
+
+use image::{DynamicImage, Rgb, RgbImage};
+
+use super::color::{lab_to_rgb, rgb_to_lab};
+use super::components::LabelImage;
+
+// 聚类所用的颜色空间
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClusterSpace {
+    Rgb,
+    Lab,
+}
+
+// 聚类结果：量化后的图像、标记图（1..=count）与各类中心的 RGB 颜色
+pub struct Clustering {
+    pub image: RgbImage,
+    pub labels: LabelImage,
+    pub centers: Vec<[f32; 3]>,
+}
+
+// splitmix64 伪随机数，相同种子得到相同结果
+struct Random(u64);
+
+impl Random {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // [0, 1) 均匀分布
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+// 读取像素颜色并转换到聚类空间
+fn pixel_features(image: &DynamicImage, space: ClusterSpace) -> Vec<[f32; 3]> {
+    image
+        .to_rgb8()
+        .pixels()
+        .map(|p| {
+            let rgb = [p[0] as f32, p[1] as f32, p[2] as f32];
+            match space {
+                ClusterSpace::Rgb => rgb,
+                ClusterSpace::Lab => rgb_to_lab(rgb.map(|v| v / 255.0)),
+            }
+        })
+        .collect()
+}
+
+fn to_rgb(color: [f32; 3], space: ClusterSpace) -> [f32; 3] {
+    match space {
+        ClusterSpace::Rgb => color,
+        ClusterSpace::Lab => lab_to_rgb(color).map(|v| v.clamp(0.0, 1.0) * 255.0),
+    }
+}
+
+fn distance2(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+// 由标记与各类中心生成聚类结果
+fn clustering(
+    width: u32,
+    height: u32,
+    labels: Vec<u32>,
+    centers: Vec<[f32; 3]>,
+    space: ClusterSpace,
+) -> Clustering {
+    let centers: Vec<[f32; 3]> = centers.into_iter().map(|c| to_rgb(c, space)).collect();
+
+    let mut image = RgbImage::new(width, height);
+    for (pixel, label) in image.pixels_mut().zip(labels.iter()) {
+        let c = centers[*label as usize - 1];
+        *pixel = Rgb([c[0].round() as u8, c[1].round() as u8, c[2].round() as u8]);
+    }
+
+    Clustering {
+        image,
+        labels: LabelImage {
+            width: width as usize,
+            height: height as usize,
+            count: centers.len() as u32,
+            labels,
+        },
+        centers,
+    }
+}
+
+// k-means++ 初始化：距离已选中心越远的像素越容易被选为新中心
+fn kmeans_plus_plus(features: &[[f32; 3]], k: usize, random: &mut Random) -> Vec<[f32; 3]> {
+    let n = features.len();
+    let mut centers = vec![features[(random.next_u64() % n as u64) as usize]];
+    let mut nearest: Vec<f32> = features.iter().map(|f| distance2(f, &centers[0])).collect();
+
+    while centers.len() < k {
+        let total: f64 = nearest.iter().map(|d| *d as f64).sum();
+        if total <= 0.0 {
+            // 颜色数少于 k
+            break;
+        }
+
+        let mut target = random.next_f64() * total;
+        let mut chosen = n - 1;
+        for (index, d) in nearest.iter().enumerate() {
+            target -= *d as f64;
+            if target < 0.0 {
+                chosen = index;
+                break;
+            }
+        }
+
+        let center = features[chosen];
+        for (d, f) in nearest.iter_mut().zip(features.iter()) {
+            *d = d.min(distance2(f, &center));
+        }
+        centers.push(center);
+    }
+
+    centers
+}
+
+// k-means 颜色聚类，seed 相同时结果相同
+pub fn kmeans(
+    image: &DynamicImage,
+    k: usize,
+    space: ClusterSpace,
+    seed: u64,
+    max_iterations: usize,
+) -> Clustering {
+    let (width, height) = (image.width(), image.height());
+    let features = pixel_features(image, space);
+    if features.is_empty() {
+        return clustering(width, height, Vec::new(), Vec::new(), space);
+    }
+
+    let mut random = Random(seed);
+    let mut centers = kmeans_plus_plus(&features, k.max(1), &mut random);
+    let mut labels = vec![0_u32; features.len()];
+
+    for _ in 0..max_iterations.max(1) {
+        // 分配到最近的中心
+        let mut changed = false;
+        for (label, f) in labels.iter_mut().zip(features.iter()) {
+            let nearest = centers
+                .iter()
+                .enumerate()
+                .map(|(index, c)| (index, distance2(f, c)))
+                .fold((0, f32::MAX), |min, v| if v.1 < min.1 { v } else { min })
+                .0 as u32
+                + 1;
+
+            if *label != nearest {
+                *label = nearest;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+
+        // 更新中心，空类保持原中心
+        let mut sums = vec![[0.0_f64; 4]; centers.len()];
+        for (label, f) in labels.iter().zip(features.iter()) {
+            let sum = &mut sums[*label as usize - 1];
+            sum[0] += f[0] as f64;
+            sum[1] += f[1] as f64;
+            sum[2] += f[2] as f64;
+            sum[3] += 1.0;
+        }
+        for (center, sum) in centers.iter_mut().zip(sums.iter()) {
+            if sum[3] > 0.0 {
+                *center = [
+                    (sum[0] / sum[3]) as f32,
+                    (sum[1] / sum[3]) as f32,
+                    (sum[2] / sum[3]) as f32,
+                ];
+            }
+        }
+    }
+
+    clustering(width, height, labels, centers, space)
+}
+
+// 均值漂移滤波：每个像素在空间半径 spatial 且颜色距离小于 range 的邻域内反复取均值，直到收敛
+fn mean_shift_filter(
+    features: &[[f32; 3]],
+    width: usize,
+    height: usize,
+    spatial: f32,
+    range: f32,
+) -> Vec<[f32; 3]> {
+    const MAX_ITERATIONS: usize = 5;
+    let radius = spatial.ceil() as i64;
+    let (spatial2, range2) = (spatial * spatial, range * range);
+
+    let mut result = vec![[0.0; 3]; features.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let (mut cx, mut cy) = (x as f32, y as f32);
+            let mut color = features[y * width + x];
+
+            for _ in 0..MAX_ITERATIONS {
+                let (x0, y0) = (cx.round() as i64, cy.round() as i64);
+                let mut sum = [0.0_f32; 5];
+                let mut n = 0.0;
+
+                for ny in (y0 - radius).max(0)..=(y0 + radius).min(height as i64 - 1) {
+                    for nx in (x0 - radius).max(0)..=(x0 + radius).min(width as i64 - 1) {
+                        let (dx, dy) = (nx as f32 - cx, ny as f32 - cy);
+                        if dx * dx + dy * dy > spatial2 {
+                            continue;
+                        }
+
+                        let f = &features[ny as usize * width + nx as usize];
+                        if distance2(f, &color) > range2 {
+                            continue;
+                        }
+
+                        sum[0] += nx as f32;
+                        sum[1] += ny as f32;
+                        sum[2] += f[0];
+                        sum[3] += f[1];
+                        sum[4] += f[2];
+                        n += 1.0;
+                    }
+                }
+
+                if n == 0.0 {
+                    break;
+                }
+
+                let next = [sum[2] / n, sum[3] / n, sum[4] / n];
+                let (nx, ny) = (sum[0] / n, sum[1] / n);
+                let shift = (nx - cx).powi(2) + (ny - cy).powi(2) + distance2(&next, &color);
+                cx = nx;
+                cy = ny;
+                color = next;
+
+                if shift < 1.0 {
+                    break;
+                }
+            }
+
+            result[y * width + x] = color;
+        }
+    }
+
+    result
+}
+
+// 均值漂移分割：滤波后颜色相差小于 range/2 的相邻像素合并为一类
+pub fn mean_shift(
+    image: &DynamicImage,
+    spatial: f32,
+    range: f32,
+    space: ClusterSpace,
+) -> Clustering {
+    let (width, height) = (image.width(), image.height());
+    let (w, h) = (width as usize, height as usize);
+    let features = pixel_features(image, space);
+    let filtered = mean_shift_filter(&features, w, h, spatial.max(1.0), range.max(1.0));
+
+    let merge2 = (range / 2.0).powi(2);
+    let mut labels = vec![0_u32; w * h];
+    let mut centers = Vec::new();
+
+    for start in 0..labels.len() {
+        if labels[start] != 0 {
+            continue;
+        }
+
+        let label = centers.len() as u32 + 1;
+        let mut sum = [0.0_f64; 3];
+        let mut n = 0.0;
+        let mut queue = VecDeque::from([start]);
+        labels[start] = label;
+
+        while let Some(index) = queue.pop_front() {
+            let f = &features[index];
+            sum[0] += f[0] as f64;
+            sum[1] += f[1] as f64;
+            sum[2] += f[2] as f64;
+            n += 1.0;
+
+            let (x, y) = ((index % w) as i64, (index / w) as i64);
+            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= w as i64 || ny >= h as i64 {
+                    continue;
+                }
+
+                let neighbor = ny as usize * w + nx as usize;
+                if labels[neighbor] == 0
+                    && distance2(&filtered[neighbor], &filtered[index]) <= merge2
+                {
+                    labels[neighbor] = label;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        centers.push([
+            (sum[0] / n) as f32,
+            (sum[1] / n) as f32,
+            (sum[2] / n) as f32,
+        ]);
+    }
+
+    clustering(width, height, labels, centers, space)
+}
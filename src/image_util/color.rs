@@ -0,0 +1,421 @@
+use image::{DynamicImage, GrayImage};
+
+// 颜色空间，RGB 分量统一为 0.0~1.0
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorSpace {
+    Rgb,
+    Hsv,
+    Hsl,
+    YCbCr601,
+    YCbCr709,
+    Xyz,
+    Lab,
+    Lch,
+    Cmyk,
+}
+
+impl ColorSpace {
+    pub const ALL: [ColorSpace; 9] = [
+        ColorSpace::Rgb,
+        ColorSpace::Hsv,
+        ColorSpace::Hsl,
+        ColorSpace::YCbCr601,
+        ColorSpace::YCbCr709,
+        ColorSpace::Xyz,
+        ColorSpace::Lab,
+        ColorSpace::Lch,
+        ColorSpace::Cmyk,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorSpace::Rgb => "RGB",
+            ColorSpace::Hsv => "HSV",
+            ColorSpace::Hsl => "HSL",
+            ColorSpace::YCbCr601 => "YCbCr (BT.601)",
+            ColorSpace::YCbCr709 => "YCbCr (BT.709)",
+            ColorSpace::Xyz => "XYZ",
+            ColorSpace::Lab => "Lab",
+            ColorSpace::Lch => "LCh",
+            ColorSpace::Cmyk => "CMYK",
+        }
+    }
+
+    // 各通道名称
+    pub fn channel_names(&self) -> &'static [&'static str] {
+        match self {
+            ColorSpace::Rgb => &["R", "G", "B"],
+            ColorSpace::Hsv => &["H", "S", "V"],
+            ColorSpace::Hsl => &["H", "S", "L"],
+            ColorSpace::YCbCr601 | ColorSpace::YCbCr709 => &["Y", "Cb", "Cr"],
+            ColorSpace::Xyz => &["X", "Y", "Z"],
+            ColorSpace::Lab => &["L", "a", "b"],
+            ColorSpace::Lch => &["L", "C", "h"],
+            ColorSpace::Cmyk => &["C", "M", "Y", "K"],
+        }
+    }
+
+    // 各通道的取值范围，用于显示为灰度图
+    pub fn channel_range(&self, channel: usize) -> (f32, f32) {
+        match (self, channel) {
+            (ColorSpace::Hsv | ColorSpace::Hsl, 0) => (0.0, 360.0),
+            (ColorSpace::Xyz, 0) => (0.0, 0.95047),
+            (ColorSpace::Xyz, 2) => (0.0, 1.08883),
+            (ColorSpace::Lab | ColorSpace::Lch, 0) => (0.0, 100.0),
+            (ColorSpace::Lab, _) => (-128.0, 127.0),
+            (ColorSpace::Lch, 1) => (0.0, 150.0),
+            (ColorSpace::Lch, _) => (0.0, 360.0),
+            _ => (0.0, 1.0),
+        }
+    }
+}
+
+// YCbCr 亮度系数 (Kr, Kb)
+const BT601: (f32, f32) = (0.299, 0.114);
+const BT709: (f32, f32) = (0.2126, 0.0722);
+
+// D65 白点
+const WHITE: [f32; 3] = [0.95047, 1.0, 1.08883];
+
+pub fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+// 色相（角度）
+fn hue(rgb: [f32; 3], max: f32, delta: f32) -> f32 {
+    let [r, g, b] = rgb;
+    if delta == 0.0 {
+        return 0.0;
+    }
+
+    let h = if max == r {
+        (g - b) / delta
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+
+    (h * 60.0).rem_euclid(360.0)
+}
+
+// 由色相与色度还原 RGB，m 为最小分量
+fn from_hue(h: f32, chroma: f32, m: f32) -> [f32; 3] {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    [r + m, g + m, b + m]
+}
+
+pub fn rgb_to_hsv(rgb: [f32; 3]) -> [f32; 3] {
+    let max = rgb[0].max(rgb[1]).max(rgb[2]);
+    let min = rgb[0].min(rgb[1]).min(rgb[2]);
+    let delta = max - min;
+    let s = if max > 0.0 { delta / max } else { 0.0 };
+
+    [hue(rgb, max, delta), s, max]
+}
+
+pub fn hsv_to_rgb(hsv: [f32; 3]) -> [f32; 3] {
+    let [h, s, v] = hsv;
+    let chroma = v * s;
+    from_hue(h, chroma, v - chroma)
+}
+
+pub fn rgb_to_hsl(rgb: [f32; 3]) -> [f32; 3] {
+    let max = rgb[0].max(rgb[1]).max(rgb[2]);
+    let min = rgb[0].min(rgb[1]).min(rgb[2]);
+    let delta = max - min;
+    let l = (max + min) / 2.0;
+    let s = if delta > 0.0 {
+        delta / (1.0 - (2.0 * l - 1.0).abs())
+    } else {
+        0.0
+    };
+
+    [hue(rgb, max, delta), s, l]
+}
+
+pub fn hsl_to_rgb(hsl: [f32; 3]) -> [f32; 3] {
+    let [h, s, l] = hsl;
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    from_hue(h, chroma, l - chroma / 2.0)
+}
+
+// 全范围 YCbCr，Cb、Cr 以 0.5 为中心
+fn rgb_to_ycbcr(rgb: [f32; 3], (kr, kb): (f32, f32)) -> [f32; 3] {
+    let [r, g, b] = rgb;
+    let y = kr * r + (1.0 - kr - kb) * g + kb * b;
+
+    [
+        y,
+        (b - y) / (2.0 * (1.0 - kb)) + 0.5,
+        (r - y) / (2.0 * (1.0 - kr)) + 0.5,
+    ]
+}
+
+fn ycbcr_to_rgb(ycbcr: [f32; 3], (kr, kb): (f32, f32)) -> [f32; 3] {
+    let [y, cb, cr] = ycbcr;
+    let r = y + 2.0 * (1.0 - kr) * (cr - 0.5);
+    let b = y + 2.0 * (1.0 - kb) * (cb - 0.5);
+    let g = (y - kr * r - kb * b) / (1.0 - kr - kb);
+
+    [r, g, b]
+}
+
+pub fn rgb_to_ycbcr601(rgb: [f32; 3]) -> [f32; 3] {
+    rgb_to_ycbcr(rgb, BT601)
+}
+
+pub fn ycbcr601_to_rgb(ycbcr: [f32; 3]) -> [f32; 3] {
+    ycbcr_to_rgb(ycbcr, BT601)
+}
+
+pub fn rgb_to_ycbcr709(rgb: [f32; 3]) -> [f32; 3] {
+    rgb_to_ycbcr(rgb, BT709)
+}
+
+pub fn ycbcr709_to_rgb(ycbcr: [f32; 3]) -> [f32; 3] {
+    ycbcr_to_rgb(ycbcr, BT709)
+}
+
+// sRGB 转 CIE XYZ（D65），Y 取值 0.0~1.0
+pub fn rgb_to_xyz(rgb: [f32; 3]) -> [f32; 3] {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+
+    [
+        0.4124564 * r + 0.3575761 * g + 0.1804375 * b,
+        0.2126729 * r + 0.7151522 * g + 0.072175 * b,
+        0.0193339 * r + 0.119192 * g + 0.9503041 * b,
+    ]
+}
+
+pub fn xyz_to_rgb(xyz: [f32; 3]) -> [f32; 3] {
+    let [x, y, z] = xyz;
+
+    [
+        3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+        -0.969266 * x + 1.8760108 * y + 0.041556 * z,
+        0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+    ]
+    .map(linear_to_srgb)
+}
+
+pub fn xyz_to_lab(xyz: [f32; 3]) -> [f32; 3] {
+    let f = |t: f32| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let fx = f(xyz[0] / WHITE[0]);
+    let fy = f(xyz[1] / WHITE[1]);
+    let fz = f(xyz[2] / WHITE[2]);
+
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+pub fn lab_to_xyz(lab: [f32; 3]) -> [f32; 3] {
+    let fy = (lab[0] + 16.0) / 116.0;
+    let fx = fy + lab[1] / 500.0;
+    let fz = fy - lab[2] / 200.0;
+
+    let f = |t: f32| {
+        if t > 6.0 / 29.0 {
+            t * t * t
+        } else {
+            (116.0 * t - 16.0) * 27.0 / 24389.0
+        }
+    };
+
+    [f(fx) * WHITE[0], f(fy) * WHITE[1], f(fz) * WHITE[2]]
+}
+
+pub fn rgb_to_lab(rgb: [f32; 3]) -> [f32; 3] {
+    xyz_to_lab(rgb_to_xyz(rgb))
+}
+
+pub fn lab_to_rgb(lab: [f32; 3]) -> [f32; 3] {
+    xyz_to_rgb(lab_to_xyz(lab))
+}
+
+// Lab 的极坐标形式，h 为角度
+pub fn lab_to_lch(lab: [f32; 3]) -> [f32; 3] {
+    let [l, a, b] = lab;
+    [l, a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.0)]
+}
+
+pub fn lch_to_lab(lch: [f32; 3]) -> [f32; 3] {
+    let [l, c, h] = lch;
+    let h = h.to_radians();
+    [l, c * h.cos(), c * h.sin()]
+}
+
+pub fn rgb_to_cmyk(rgb: [f32; 3]) -> [f32; 4] {
+    let k = 1.0 - rgb[0].max(rgb[1]).max(rgb[2]);
+    if k >= 1.0 {
+        return [0.0, 0.0, 0.0, 1.0];
+    }
+
+    let [c, m, y] = rgb.map(|v| (1.0 - v - k) / (1.0 - k));
+    [c, m, y, k]
+}
+
+pub fn cmyk_to_rgb(cmyk: [f32; 4]) -> [f32; 3] {
+    let [c, m, y, k] = cmyk;
+    [c, m, y].map(|v| (1.0 - v) * (1.0 - k))
+}
+
+// RGB 转换到指定颜色空间，三通道颜色空间的第四个分量为 0
+pub fn convert_from_rgb(rgb: [f32; 3], space: ColorSpace) -> [f32; 4] {
+    let [a, b, c] = match space {
+        ColorSpace::Rgb => rgb,
+        ColorSpace::Hsv => rgb_to_hsv(rgb),
+        ColorSpace::Hsl => rgb_to_hsl(rgb),
+        ColorSpace::YCbCr601 => rgb_to_ycbcr601(rgb),
+        ColorSpace::YCbCr709 => rgb_to_ycbcr709(rgb),
+        ColorSpace::Xyz => rgb_to_xyz(rgb),
+        ColorSpace::Lab => rgb_to_lab(rgb),
+        ColorSpace::Lch => lab_to_lch(rgb_to_lab(rgb)),
+        ColorSpace::Cmyk => return rgb_to_cmyk(rgb),
+    };
+
+    [a, b, c, 0.0]
+}
+
+// 由指定颜色空间转换回 RGB，结果截断到 0.0~1.0
+pub fn convert_to_rgb(color: [f32; 4], space: ColorSpace) -> [f32; 3] {
+    let [a, b, c, _] = color;
+    let rgb = match space {
+        ColorSpace::Rgb => [a, b, c],
+        ColorSpace::Hsv => hsv_to_rgb([a, b, c]),
+        ColorSpace::Hsl => hsl_to_rgb([a, b, c]),
+        ColorSpace::YCbCr601 => ycbcr601_to_rgb([a, b, c]),
+        ColorSpace::YCbCr709 => ycbcr709_to_rgb([a, b, c]),
+        ColorSpace::Xyz => xyz_to_rgb([a, b, c]),
+        ColorSpace::Lab => lab_to_rgb([a, b, c]),
+        ColorSpace::Lch => lab_to_rgb(lch_to_lab([a, b, c])),
+        ColorSpace::Cmyk => cmyk_to_rgb(color),
+    };
+
+    rgb.map(|v| v.clamp(0.0, 1.0))
+}
+
+// 将图像转换到指定颜色空间并按通道拆分
+pub fn split_color_space(image: &DynamicImage, space: ColorSpace) -> Vec<Vec<f32>> {
+    let count = space.channel_names().len();
+    let image = image.to_rgb32f();
+
+    let mut channels = vec![Vec::with_capacity(image.len() / 3); count];
+    for pixel in image.pixels() {
+        let color = convert_from_rgb([pixel[0], pixel[1], pixel[2]], space);
+        for (channel, v) in channels.iter_mut().zip(color.iter()) {
+            channel.push(*v);
+        }
+    }
+
+    channels
+}
+
+// 将某一通道按取值范围线性映射为灰度图
+pub fn channel_image(image: &DynamicImage, space: ColorSpace, channel: usize) -> GrayImage {
+    let channels = split_color_space(image, space);
+    let (min, max) = space.channel_range(channel);
+
+    let buffer: Vec<u8> = channels[channel]
+        .iter()
+        .map(|v| ((v - min) / (max - min) * 255.0).round().clamp(0.0, 255.0) as u8)
+        .collect();
+
+    GrayImage::from_vec(image.width(), image.height(), buffer).unwrap()
+}
+
+// 灰度化标准
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GrayStandard {
+    Bt601,
+    Bt709,
+    // 三通道平均
+    Average,
+    // 最大与最小分量的平均
+    Lightness,
+    // 取单个通道
+    Channel(usize),
+}
+
+impl GrayStandard {
+    pub fn name(&self) -> String {
+        match self {
+            GrayStandard::Bt601 => "BT.601".to_string(),
+            GrayStandard::Bt709 => "BT.709".to_string(),
+            GrayStandard::Average => "平均值".to_string(),
+            GrayStandard::Lightness => "明度".to_string(),
+            GrayStandard::Channel(c) => format!("{}通道", ["R", "G", "B"][(*c).min(2)]),
+        }
+    }
+}
+
+// 按灰度化标准计算灰度，与分量的取值范围无关
+pub fn rgb_to_gray(rgb: [f32; 3], standard: GrayStandard) -> f32 {
+    let [r, g, b] = rgb;
+    match standard {
+        GrayStandard::Bt601 => 0.299 * r + 0.587 * g + 0.114 * b,
+        GrayStandard::Bt709 => 0.2126 * r + 0.7152 * g + 0.0722 * b,
+        GrayStandard::Average => (r + g + b) / 3.0,
+        GrayStandard::Lightness => (r.max(g).max(b) + r.min(g).min(b)) / 2.0,
+        GrayStandard::Channel(c) => rgb[c.min(2)],
+    }
+}
+
+// 按指定标准灰度化，灰度图直接读取亮度通道
+pub fn image_to_gray_standard(image: &DynamicImage, standard: GrayStandard) -> Vec<u8> {
+    if !image.color().has_color() {
+        return image.to_luma8().into_raw();
+    }
+
+    image
+        .to_rgb8()
+        .pixels()
+        .map(|p| {
+            let gray = rgb_to_gray([p[0] as f32, p[1] as f32, p[2] as f32], standard);
+            gray.round() as u8
+        })
+        .collect()
+}
+
+// 按指定标准灰度化为16位灰度，16位及浮点图像不损失精度
+pub fn image_to_gray16_standard(image: &DynamicImage, standard: GrayStandard) -> Vec<u16> {
+    if !image.color().has_color() {
+        return image.to_luma16().into_raw();
+    }
+
+    image
+        .to_rgb16()
+        .pixels()
+        .map(|p| {
+            let gray = rgb_to_gray([p[0] as f32, p[1] as f32, p[2] as f32], standard);
+            gray.round().min(65535.0) as u16
+        })
+        .collect()
+}
@@ -0,0 +1,155 @@
+use image::{DynamicImage, Rgb, RgbImage};
+
+use super::image_util::image_to_gray32f;
+
+// 伪彩色映射表
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Colormap {
+    Viridis,
+    Magma,
+    Inferno,
+    Jet,
+    Hot,
+    Gray,
+    // 蓝-白-红发散映射，适合以中间值为零点的数据
+    CoolWarm,
+}
+
+// matplotlib 中 viridis、magma、inferno 的等距采样点
+const VIRIDIS: [[u8; 3]; 9] = [
+    [68, 1, 84],
+    [72, 40, 120],
+    [62, 73, 137],
+    [49, 104, 142],
+    [38, 130, 142],
+    [31, 158, 137],
+    [53, 183, 121],
+    [110, 206, 88],
+    [253, 231, 37],
+];
+
+const MAGMA: [[u8; 3]; 9] = [
+    [0, 0, 4],
+    [28, 16, 68],
+    [79, 18, 123],
+    [129, 37, 129],
+    [181, 54, 122],
+    [229, 89, 100],
+    [251, 135, 97],
+    [254, 194, 135],
+    [252, 253, 191],
+];
+
+const INFERNO: [[u8; 3]; 9] = [
+    [0, 0, 4],
+    [31, 12, 72],
+    [85, 15, 109],
+    [136, 34, 106],
+    [186, 54, 85],
+    [227, 89, 51],
+    [249, 142, 9],
+    [249, 203, 53],
+    [252, 255, 164],
+];
+
+// Moreland 的 cool-warm 发散映射
+const COOL_WARM: [[u8; 3]; 5] = [
+    [59, 76, 192],
+    [141, 176, 254],
+    [221, 221, 221],
+    [244, 154, 123],
+    [180, 4, 38],
+];
+
+// 等距采样点之间线性插值
+fn interpolate(points: &[[u8; 3]], t: f32) -> [f32; 3] {
+    let position = t * (points.len() - 1) as f32;
+    let index = (position.floor() as usize).min(points.len() - 2);
+    let fraction = position - index as f32;
+    let (a, b) = (points[index], points[index + 1]);
+
+    [0, 1, 2].map(|c| (a[c] as f32 + (b[c] as f32 - a[c] as f32) * fraction) / 255.0)
+}
+
+impl Colormap {
+    pub const ALL: [Colormap; 7] = [
+        Colormap::Viridis,
+        Colormap::Magma,
+        Colormap::Inferno,
+        Colormap::Jet,
+        Colormap::Hot,
+        Colormap::Gray,
+        Colormap::CoolWarm,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Colormap::Viridis => "viridis",
+            Colormap::Magma => "magma",
+            Colormap::Inferno => "inferno",
+            Colormap::Jet => "jet",
+            Colormap::Hot => "hot",
+            Colormap::Gray => "gray",
+            Colormap::CoolWarm => "coolwarm",
+        }
+    }
+
+    // t 为 0.0~1.0 时对应的颜色
+    pub fn color(&self, t: f32) -> [u8; 3] {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let rgb = match self {
+            Colormap::Viridis => interpolate(&VIRIDIS, t),
+            Colormap::Magma => interpolate(&MAGMA, t),
+            Colormap::Inferno => interpolate(&INFERNO, t),
+            Colormap::Jet => [3.0, 2.0, 1.0].map(|k| 1.5 - (4.0 * t - k).abs()),
+            Colormap::Hot => [0.0, 1.0, 2.0].map(|k| 3.0 * t - k),
+            Colormap::Gray => [t; 3],
+            Colormap::CoolWarm => interpolate(&COOL_WARM, t),
+        };
+
+        rgb.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    // 256 级查找表
+    pub fn lut(&self) -> Vec<[u8; 3]> {
+        (0..256).map(|v| self.color(v as f32 / 255.0)).collect()
+    }
+}
+
+// 灰度值的范围，忽略非有限值
+pub fn intensity_range(image: &DynamicImage) -> (f32, f32) {
+    image_to_gray32f(image)
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold((f32::MAX, f32::MIN), |(min, max), v| {
+            (min.min(*v), max.max(*v))
+        })
+}
+
+// 按亮度映射为伪彩色，range 为映射到色表两端的灰度范围（0.0~1.0），None 时使用图像的最小最大值
+pub fn colormap_image(image: &DynamicImage, map: Colormap, range: Option<(f32, f32)>) -> RgbImage {
+    let (low, high) = range.unwrap_or_else(|| intensity_range(image));
+    let scale = if high > low { 1.0 / (high - low) } else { 0.0 };
+
+    let lut = map.lut();
+    let gray = image_to_gray32f(image);
+    let mut buffer = RgbImage::new(image.width(), image.height());
+    for (pixel, v) in buffer.pixels_mut().zip(gray.iter()) {
+        let t = ((v - low) * scale).clamp(0.0, 1.0);
+        *pixel = Rgb(lut[(t * 255.0).round() as usize]);
+    }
+
+    buffer
+}
+
+// 色标图像，vertical 时高值在上，否则高值在右
+pub fn colorbar_image(map: Colormap, width: u32, height: u32, vertical: bool) -> RgbImage {
+    RgbImage::from_fn(width, height, |x, y| {
+        let t = if vertical {
+            1.0 - y as f32 / (height.max(2) - 1) as f32
+        } else {
+            x as f32 / (width.max(2) - 1) as f32
+        };
+        Rgb(map.color(t))
+    })
+}
@@ -0,0 +1,302 @@
+use std::fs::File;
+use std::io::Write;
+
+use image::{DynamicImage, GrayImage, Rgb, RgbImage};
+
+use super::image_util::image_to_gray;
+
+// 像素连通方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+
+impl Connectivity {
+    // 相邻像素的偏移量
+    pub fn offsets(&self) -> &'static [(i64, i64)] {
+        match self {
+            Connectivity::Four => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Connectivity::Eight => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+// 标记图像，0 为背景，1..=count 为各连通域
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabelImage {
+    pub width: usize,
+    pub height: usize,
+    pub labels: Vec<u32>,
+    pub count: u32,
+}
+
+impl LabelImage {
+    pub fn get(&self, x: usize, y: usize) -> u32 {
+        self.labels[y * self.width + x]
+    }
+}
+
+// 并查集查找，同时压缩路径
+fn find(parent: &mut [u32], mut x: u32) -> u32 {
+    while parent[x as usize] != x {
+        parent[x as usize] = parent[parent[x as usize] as usize];
+        x = parent[x as usize];
+    }
+
+    x
+}
+
+// 连通域标记，非零像素为前景
+// 两遍扫描：第一遍分配临时标号并记录等价关系，第二遍合并为连续标号
+pub fn label_components(binary: &GrayImage, connectivity: Connectivity) -> LabelImage {
+    let width = binary.width() as usize;
+    let height = binary.height() as usize;
+    let data = binary.as_raw();
+
+    // 只需检查已扫描过的邻居
+    let previous: &[(i64, i64)] = match connectivity {
+        Connectivity::Four => &[(-1, 0), (0, -1)],
+        Connectivity::Eight => &[(-1, 0), (-1, -1), (0, -1), (1, -1)],
+    };
+
+    let mut labels = vec![0_u32; width * height];
+    let mut parent: Vec<u32> = vec![0];
+
+    for y in 0..height {
+        for x in 0..width {
+            if data[y * width + x] == 0 {
+                continue;
+            }
+
+            let mut label = 0;
+            for (dx, dy) in previous {
+                let nx = x as i64 + dx;
+                let ny = y as i64 + dy;
+                if nx < 0 || ny < 0 || nx >= width as i64 {
+                    continue;
+                }
+
+                let neighbour = labels[ny as usize * width + nx as usize];
+                if neighbour == 0 {
+                    continue;
+                }
+
+                if label == 0 {
+                    label = find(&mut parent, neighbour);
+                } else {
+                    let a = find(&mut parent, label);
+                    let b = find(&mut parent, neighbour);
+                    if a != b {
+                        parent[a.max(b) as usize] = a.min(b);
+                        label = a.min(b);
+                    }
+                }
+            }
+
+            if label == 0 {
+                label = parent.len() as u32;
+                parent.push(label);
+            }
+
+            labels[y * width + x] = label;
+        }
+    }
+
+    // 重新编号为连续标号
+    let mut remap = vec![0_u32; parent.len()];
+    let mut count = 0;
+    for k in 1..parent.len() as u32 {
+        let root = find(&mut parent, k);
+        if remap[root as usize] == 0 {
+            count += 1;
+            remap[root as usize] = count;
+        }
+        remap[k as usize] = remap[root as usize];
+    }
+
+    for v in labels.iter_mut() {
+        *v = remap[*v as usize];
+    }
+
+    LabelImage {
+        width,
+        height,
+        labels,
+        count,
+    }
+}
+
+// 连通域统计量
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComponentStats {
+    pub label: u32,
+    // 像素数
+    pub area: u32,
+    // 外接矩形 [x, y, 宽, 高]
+    pub bounding_box: [u32; 4],
+    pub centroid: (f64, f64),
+    // 与背景或其他区域相邻的像素边数
+    pub perimeter: u32,
+    // 等效椭圆离心率，0 为圆，趋近 1 为细长
+    pub eccentricity: f64,
+    // 等效椭圆长轴与 x 轴夹角（弧度）
+    pub orientation: f64,
+    // 原图中该区域的平均灰度
+    pub mean_intensity: f64,
+}
+
+// 计算各连通域的统计量
+pub fn component_stats(labels: &LabelImage, source: &DynamicImage) -> Vec<ComponentStats> {
+    let width = labels.width;
+    let height = labels.height;
+    let gray = image_to_gray(source);
+    let count = labels.count as usize;
+
+    let mut area = vec![0_u32; count + 1];
+    let mut bounds = vec![[u32::MAX, u32::MAX, 0, 0]; count + 1];
+    let mut sum = vec![(0.0_f64, 0.0_f64); count + 1];
+    let mut square_sum = vec![(0.0_f64, 0.0_f64, 0.0_f64); count + 1];
+    let mut perimeter = vec![0_u32; count + 1];
+    let mut intensity = vec![0.0_f64; count + 1];
+
+    for y in 0..height {
+        for x in 0..width {
+            let label = labels.get(x, y) as usize;
+            if label == 0 {
+                continue;
+            }
+
+            let (fx, fy) = (x as f64, y as f64);
+            area[label] += 1;
+            bounds[label][0] = bounds[label][0].min(x as u32);
+            bounds[label][1] = bounds[label][1].min(y as u32);
+            bounds[label][2] = bounds[label][2].max(x as u32);
+            bounds[label][3] = bounds[label][3].max(y as u32);
+            sum[label].0 += fx;
+            sum[label].1 += fy;
+            square_sum[label].0 += fx * fx;
+            square_sum[label].1 += fy * fy;
+            square_sum[label].2 += fx * fy;
+            intensity[label] += gray[y * width + x] as f64;
+
+            for (dx, dy) in Connectivity::Four.offsets() {
+                let nx = x as i64 + dx;
+                let ny = y as i64 + dy;
+                if nx < 0
+                    || ny < 0
+                    || nx >= width as i64
+                    || ny >= height as i64
+                    || labels.get(nx as usize, ny as usize) as usize != label
+                {
+                    perimeter[label] += 1;
+                }
+            }
+        }
+    }
+
+    (1..=count)
+        .map(|label| {
+            let n = area[label] as f64;
+            let cx = sum[label].0 / n;
+            let cy = sum[label].1 / n;
+
+            // 二阶中心矩
+            let mu20 = square_sum[label].0 / n - cx * cx;
+            let mu02 = square_sum[label].1 / n - cy * cy;
+            let mu11 = square_sum[label].2 / n - cx * cy;
+
+            let common = ((mu20 - mu02) * (mu20 - mu02) + 4.0 * mu11 * mu11).sqrt();
+            let major = (mu20 + mu02 + common) / 2.0;
+            let minor = (mu20 + mu02 - common) / 2.0;
+            let eccentricity = if major > 0.0 {
+                (1.0 - minor.max(0.0) / major).sqrt()
+            } else {
+                0.0
+            };
+
+            let [x0, y0, x1, y1] = bounds[label];
+
+            ComponentStats {
+                label: label as u32,
+                area: area[label],
+                bounding_box: [x0, y0, x1 - x0 + 1, y1 - y0 + 1],
+                centroid: (cx, cy),
+                perimeter: perimeter[label],
+                eccentricity,
+                orientation: 0.5 * (2.0 * mu11).atan2(mu20 - mu02),
+                mean_intensity: intensity[label] / n,
+            }
+        })
+        .collect()
+}
+
+// 由标号生成固定的随机颜色
+fn label_color(label: u32) -> Rgb<u8> {
+    let mut x = label as u64 ^ 0x9E37_79B9_7F4A_7C15;
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+
+    // 避免颜色过暗，与黑色背景区分
+    Rgb([
+        64 + (x & 0xBF) as u8,
+        64 + ((x >> 8) & 0xBF) as u8,
+        64 + ((x >> 16) & 0xBF) as u8,
+    ])
+}
+
+// 以随机颜色显示各连通域，背景为黑色
+pub fn colorize_labels(labels: &LabelImage) -> RgbImage {
+    RgbImage::from_fn(
+        labels.width as u32,
+        labels.height as u32,
+        |x, y| match labels.get(x as usize, y as usize) {
+            0 => Rgb([0, 0, 0]),
+            label => label_color(label),
+        },
+    )
+}
+
+// 统计量转为 CSV 文本
+pub fn stats_to_csv(stats: &[ComponentStats]) -> String {
+    let mut csv = String::from(
+        "label,area,bbox_x,bbox_y,bbox_width,bbox_height,centroid_x,centroid_y,perimeter,eccentricity,orientation,mean_intensity\n",
+    );
+
+    for s in stats {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{:.3},{:.3},{},{:.4},{:.4},{:.3}\n",
+            s.label,
+            s.area,
+            s.bounding_box[0],
+            s.bounding_box[1],
+            s.bounding_box[2],
+            s.bounding_box[3],
+            s.centroid.0,
+            s.centroid.1,
+            s.perimeter,
+            s.eccentricity,
+            s.orientation,
+            s.mean_intensity,
+        ));
+    }
+
+    csv
+}
+
+// 导出统计量到 CSV 文件
+pub fn save_stats_csv(stats: &[ComponentStats], file: &str) -> Result<(), String> {
+    let mut f = File::create(file).map_err(|e| format!("无法创建文件：{}", e))?;
+    f.write_all(stats_to_csv(stats).as_bytes())
+        .map_err(|e| format!("无法写入文件：{}", e))
+}
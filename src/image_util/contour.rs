@@ -0,0 +1,604 @@
+use std::fs::File;
+use std::io::Write;
+
+use image::{DynamicImage, GrayImage, Rgba, RgbaImage};
+
+// 轮廓，点按边界跟踪顺序排列
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    pub points: Vec<(i32, i32)>,
+    // 是否为孔洞边界
+    pub is_hole: bool,
+    // 包含该轮廓的上一级轮廓下标
+    pub parent: Option<usize>,
+}
+
+// 8邻域方向，下标递增为逆时针（y 轴向下）
+const DIRECTIONS: [(i64, i64); 8] = [
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+fn direction(from: (i64, i64), to: (i64, i64)) -> usize {
+    let d = (to.0 - from.0, to.1 - from.1);
+    DIRECTIONS.iter().position(|v| *v == d).unwrap()
+}
+
+// Suzuki-Abe 边界跟踪，非零像素为前景，返回全部外边界与孔洞边界及其层级关系
+pub fn find_contours(binary: &GrayImage) -> Vec<Contour> {
+    let width = binary.width() as usize + 2;
+    let height = binary.height() as usize + 2;
+
+    // 四周补一圈背景
+    let mut f = vec![0_i32; width * height];
+    for (x, y, v) in binary.enumerate_pixels() {
+        if v[0] > 0 {
+            f[(y as usize + 1) * width + x as usize + 1] = 1;
+        }
+    }
+
+    let at = |i: i64, j: i64| i as usize * width + j as usize;
+
+    // 按边界编号 NBD 记录 (是否孔洞, 上一级 NBD)，1 为图像外框
+    let mut borders: Vec<(bool, i32)> = vec![(true, 0), (true, 0)];
+    let mut contours: Vec<Contour> = Vec::new();
+
+    for i in 1..height as i64 - 1 {
+        let mut lnbd = 1;
+        for j in 1..width as i64 - 1 {
+            let value = f[at(i, j)];
+            let start = if value == 1 && f[at(i, j - 1)] == 0 {
+                Some((false, (i, j - 1)))
+            } else if value >= 1 && f[at(i, j + 1)] == 0 {
+                if value > 1 {
+                    lnbd = value;
+                }
+                Some((true, (i, j + 1)))
+            } else {
+                None
+            };
+
+            if let Some((is_hole, from)) = start {
+                let nbd = borders.len() as i32;
+
+                // 由上一个边界类型确定父边界
+                let (last_is_hole, last_parent) = borders[lnbd as usize];
+                let parent = if is_hole == last_is_hole {
+                    last_parent
+                } else {
+                    lnbd
+                };
+                borders.push((is_hole, parent));
+
+                let points = follow_border(&mut f, width, (i, j), from, nbd);
+                contours.push(Contour {
+                    points,
+                    is_hole,
+                    parent: if parent > 1 {
+                        Some(parent as usize - 2)
+                    } else {
+                        None
+                    },
+                });
+            }
+
+            let value = f[at(i, j)];
+            if value != 1 && value != 0 {
+                lnbd = value.abs();
+            }
+        }
+    }
+
+    contours
+}
+
+// 从 start 出发跟踪一条边界并标记 nbd，from 为起始的背景邻点
+fn follow_border(
+    f: &mut [i32],
+    width: usize,
+    start: (i64, i64),
+    from: (i64, i64),
+    nbd: i32,
+) -> Vec<(i32, i32)> {
+    let at = |p: (i64, i64)| p.0 as usize * width + p.1 as usize;
+    let step = |p: (i64, i64), d: usize| (p.0 + DIRECTIONS[d].0, p.1 + DIRECTIONS[d].1);
+    let point = |p: (i64, i64)| (p.1 as i32 - 1, p.0 as i32 - 1);
+
+    // 顺时针寻找第一个前景邻点
+    let d0 = direction(start, from);
+    let first = (0..8)
+        .map(|k| (d0 + 8 - k) % 8)
+        .map(|d| step(start, d))
+        .find(|p| f[at(*p)] != 0);
+
+    let first = match first {
+        Some(p) => p,
+        None => {
+            // 孤立点
+            f[at(start)] = -nbd;
+            return vec![point(start)];
+        }
+    };
+
+    let mut points = Vec::new();
+    let mut previous = first;
+    let mut current = start;
+    loop {
+        points.push(point(current));
+
+        // 从上一点的下一个方向开始逆时针寻找前景邻点
+        let d = direction(current, previous);
+        let mut east_is_background = false;
+        let mut next = current;
+        for k in 1..=8 {
+            let d = (d + k) % 8;
+            let p = step(current, d);
+            if f[at(p)] != 0 {
+                next = p;
+                break;
+            }
+            if d == 0 {
+                east_is_background = true;
+            }
+        }
+
+        if east_is_background {
+            f[at(current)] = -nbd;
+        } else if f[at(current)] == 1 {
+            f[at(current)] = nbd;
+        }
+
+        if next == start && current == first {
+            break;
+        }
+
+        previous = current;
+        current = next;
+    }
+
+    points
+}
+
+// 点到线段所在直线的距离
+fn line_distance(p: (i32, i32), a: (i32, i32), b: (i32, i32)) -> f64 {
+    let (px, py) = (p.0 as f64, p.1 as f64);
+    let (ax, ay) = (a.0 as f64, a.1 as f64);
+    let (bx, by) = (b.0 as f64, b.1 as f64);
+
+    let length = ((bx - ax) * (bx - ax) + (by - ay) * (by - ay)).sqrt();
+    if length == 0.0 {
+        return ((px - ax) * (px - ax) + (py - ay) * (py - ay)).sqrt();
+    }
+
+    ((by - ay) * px - (bx - ax) * py + bx * ay - by * ax).abs() / length
+}
+
+// Douglas-Peucker 折线简化，保留首尾点
+pub fn douglas_peucker(points: &[(i32, i32)], epsilon: f64) -> Vec<(i32, i32)> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let first = points[0];
+    let last = points[points.len() - 1];
+
+    let (index, distance) = points[1..points.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(k, p)| (k + 1, line_distance(*p, first, last)))
+        .fold((0, -1.0), |max, v| if v.1 > max.1 { v } else { max });
+
+    if distance > epsilon {
+        let mut left = douglas_peucker(&points[..=index], epsilon);
+        let right = douglas_peucker(&points[index..], epsilon);
+        left.pop();
+        left.extend(right);
+        left
+    } else {
+        vec![first, last]
+    }
+}
+
+// 闭合轮廓简化，以距起点最远的点将轮廓分为两段分别简化
+pub fn simplify_contour(points: &[(i32, i32)], epsilon: f64) -> Vec<(i32, i32)> {
+    if points.len() < 4 {
+        return points.to_vec();
+    }
+
+    let first = points[0];
+    let far = (1..points.len())
+        .max_by_key(|k| {
+            let (dx, dy) = (points[*k].0 - first.0, points[*k].1 - first.1);
+            dx as i64 * dx as i64 + dy as i64 * dy as i64
+        })
+        .unwrap();
+
+    let mut closed = points.to_vec();
+    closed.push(first);
+
+    let mut result = douglas_peucker(&closed[..=far], epsilon);
+    result.pop();
+    result.extend(douglas_peucker(&closed[far..], epsilon));
+    result.pop();
+
+    result
+}
+
+fn cross(o: (i32, i32), a: (i32, i32), b: (i32, i32)) -> i64 {
+    (a.0 - o.0) as i64 * (b.1 - o.1) as i64 - (a.1 - o.1) as i64 * (b.0 - o.0) as i64
+}
+
+// 凸包（Andrew 单调链算法）
+pub fn convex_hull(points: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let mut points = points.to_vec();
+    points.sort();
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let mut hull: Vec<(i32, i32)> = Vec::with_capacity(points.len() * 2);
+    for pass in 0..2 {
+        let start = hull.len();
+        let iter: Box<dyn Iterator<Item = &(i32, i32)>> = if pass == 0 {
+            Box::new(points.iter())
+        } else {
+            Box::new(points.iter().rev())
+        };
+
+        for p in iter {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], *p) <= 0
+            {
+                hull.pop();
+            }
+            hull.push(*p);
+        }
+        hull.pop();
+    }
+
+    hull
+}
+
+// 外接矩形 [x, y, 宽, 高]
+pub fn bounding_rect(points: &[(i32, i32)]) -> [i32; 4] {
+    let x0 = points.iter().map(|p| p.0).min().unwrap_or(0);
+    let y0 = points.iter().map(|p| p.1).min().unwrap_or(0);
+    let x1 = points.iter().map(|p| p.0).max().unwrap_or(-1);
+    let y1 = points.iter().map(|p| p.1).max().unwrap_or(-1);
+
+    [x0, y0, x1 - x0 + 1, y1 - y0 + 1]
+}
+
+// 旋转矩形
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RotatedRect {
+    pub center: (f64, f64),
+    pub size: (f64, f64),
+    // 宽边与 x 轴夹角（弧度）
+    pub angle: f64,
+}
+
+impl RotatedRect {
+    // 四个顶点
+    pub fn corners(&self) -> [(f64, f64); 4] {
+        let (cos, sin) = (self.angle.cos(), self.angle.sin());
+        let (w, h) = (self.size.0 / 2.0, self.size.1 / 2.0);
+
+        [(-w, -h), (w, -h), (w, h), (-w, h)].map(|(x, y)| {
+            (
+                self.center.0 + x * cos - y * sin,
+                self.center.1 + x * sin + y * cos,
+            )
+        })
+    }
+}
+
+// 最小面积外接矩形（旋转卡壳），矩形的一条边必与凸包的某条边重合
+pub fn min_area_rect(points: &[(i32, i32)]) -> RotatedRect {
+    let hull = convex_hull(points);
+    if hull.len() < 3 {
+        let [x, y, w, h] = bounding_rect(&hull);
+        return RotatedRect {
+            center: (
+                x as f64 + (w - 1) as f64 / 2.0,
+                y as f64 + (h - 1) as f64 / 2.0,
+            ),
+            size: ((w - 1).max(0) as f64, (h - 1).max(0) as f64),
+            angle: 0.0,
+        };
+    }
+
+    let mut best = RotatedRect {
+        center: (0.0, 0.0),
+        size: (f64::MAX, f64::MAX),
+        angle: 0.0,
+    };
+
+    for k in 0..hull.len() {
+        let a = hull[k];
+        let b = hull[(k + 1) % hull.len()];
+        let angle = ((b.1 - a.1) as f64).atan2((b.0 - a.0) as f64);
+        let (cos, sin) = (angle.cos(), angle.sin());
+
+        // 投影到边方向与其法向
+        let mut min = (f64::MAX, f64::MAX);
+        let mut max = (f64::MIN, f64::MIN);
+        for p in &hull {
+            let u = p.0 as f64 * cos + p.1 as f64 * sin;
+            let v = -(p.0 as f64) * sin + p.1 as f64 * cos;
+            min = (min.0.min(u), min.1.min(v));
+            max = (max.0.max(u), max.1.max(v));
+        }
+
+        let size = (max.0 - min.0, max.1 - min.1);
+        if size.0 * size.1 < best.size.0 * best.size.1 {
+            let u = (min.0 + max.0) / 2.0;
+            let v = (min.1 + max.1) / 2.0;
+            best = RotatedRect {
+                center: (u * cos - v * sin, u * sin + v * cos),
+                size,
+                angle,
+            };
+        }
+    }
+
+    best
+}
+
+// 圆 (圆心, 半径)
+pub type Circle = ((f64, f64), f64);
+
+fn circle_from_two(a: (f64, f64), b: (f64, f64)) -> Circle {
+    let center = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    (center, ((a.0 - b.0).hypot(a.1 - b.1)) / 2.0)
+}
+
+fn circle_from_three(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Circle {
+    let d = 2.0 * (a.0 * (b.1 - c.1) + b.0 * (c.1 - a.1) + c.0 * (a.1 - b.1));
+    if d.abs() < 1e-12 {
+        // 三点共线，取最远两点
+        let candidates = [
+            circle_from_two(a, b),
+            circle_from_two(a, c),
+            circle_from_two(b, c),
+        ];
+        return candidates.into_iter().fold(
+            ((0.0, 0.0), -1.0),
+            |max, v| if v.1 > max.1 { v } else { max },
+        );
+    }
+
+    let sa = a.0 * a.0 + a.1 * a.1;
+    let sb = b.0 * b.0 + b.1 * b.1;
+    let sc = c.0 * c.0 + c.1 * c.1;
+    let x = (sa * (b.1 - c.1) + sb * (c.1 - a.1) + sc * (a.1 - b.1)) / d;
+    let y = (sa * (c.0 - b.0) + sb * (a.0 - c.0) + sc * (b.0 - a.0)) / d;
+
+    ((x, y), (a.0 - x).hypot(a.1 - y))
+}
+
+fn in_circle(circle: &Circle, p: (f64, f64)) -> bool {
+    (p.0 - circle.0 .0).hypot(p.1 - circle.0 .1) <= circle.1 + 1e-7
+}
+
+// 最小外接圆（Welzl 增量算法），只需考虑凸包顶点
+pub fn min_enclosing_circle(points: &[(i32, i32)]) -> Circle {
+    let hull: Vec<(f64, f64)> = convex_hull(points)
+        .iter()
+        .map(|p| (p.0 as f64, p.1 as f64))
+        .collect();
+    if hull.is_empty() {
+        return ((0.0, 0.0), 0.0);
+    }
+
+    let mut circle = (hull[0], 0.0);
+    for i in 1..hull.len() {
+        if in_circle(&circle, hull[i]) {
+            continue;
+        }
+
+        circle = (hull[i], 0.0);
+        for j in 0..i {
+            if in_circle(&circle, hull[j]) {
+                continue;
+            }
+
+            circle = circle_from_two(hull[i], hull[j]);
+            for k in 0..j {
+                if !in_circle(&circle, hull[k]) {
+                    circle = circle_from_three(hull[i], hull[j], hull[k]);
+                }
+            }
+        }
+    }
+
+    circle
+}
+
+// Bresenham 直线经过的像素
+pub fn line_points(a: (i32, i32), b: (i32, i32)) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = a;
+    let dx = (b.0 - a.0).abs();
+    let dy = -(b.1 - a.1).abs();
+    let sx = if a.0 < b.0 { 1 } else { -1 };
+    let sy = if a.1 < b.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    let mut points = Vec::new();
+    loop {
+        points.push((x, y));
+        if x == b.0 && y == b.1 {
+            break;
+        }
+
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+
+    points
+}
+
+fn draw_line(image: &mut RgbaImage, a: (i32, i32), b: (i32, i32), color: Rgba<u8>) {
+    for (x, y) in line_points(a, b) {
+        if x >= 0 && y >= 0 && (x as u32) < image.width() && (y as u32) < image.height() {
+            image.put_pixel(x as u32, y as u32, color);
+        }
+    }
+}
+
+// 在图像上绘制闭合多边形
+pub fn draw_polygon(image: &mut RgbaImage, points: &[(i32, i32)], color: Rgba<u8>) {
+    for k in 0..points.len() {
+        draw_line(image, points[k], points[(k + 1) % points.len()], color);
+    }
+}
+
+// 在图像上绘制圆
+pub fn draw_circle(image: &mut RgbaImage, circle: &Circle, color: Rgba<u8>) {
+    let ((cx, cy), r) = *circle;
+    let steps = ((2.0 * std::f64::consts::PI * r).ceil() as usize).max(8);
+    let points: Vec<(i32, i32)> = (0..steps)
+        .map(|k| {
+            let t = k as f64 / steps as f64 * 2.0 * std::f64::consts::PI;
+            (
+                (cx + r * t.cos()).round() as i32,
+                (cy + r * t.sin()).round() as i32,
+            )
+        })
+        .collect();
+
+    draw_polygon(image, &points, color);
+}
+
+// 将轮廓叠加到图像上，外边界与孔洞边界使用不同颜色
+pub fn draw_contours(
+    image: &DynamicImage,
+    contours: &[Contour],
+    outer: Rgba<u8>,
+    hole: Rgba<u8>,
+) -> RgbaImage {
+    let mut image = image.to_rgba8();
+    for contour in contours {
+        let color = if contour.is_hole { hole } else { outer };
+        draw_polygon(&mut image, &contour.points, color);
+    }
+
+    image
+}
+
+// 导出为 SVG，每条轮廓为一个 polygon
+pub fn contours_to_svg(contours: &[Contour], width: u32, height: u32) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+        width, height
+    );
+
+    for (k, contour) in contours.iter().enumerate() {
+        let points: Vec<String> = contour
+            .points
+            .iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect();
+        let parent = match contour.parent {
+            Some(parent) => format!(" data-parent=\"{}\"", parent),
+            None => String::new(),
+        };
+
+        svg.push_str(&format!(
+            "  <polygon id=\"contour{}\" class=\"{}\"{} points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1\"/>\n",
+            k,
+            if contour.is_hole { "hole" } else { "outer" },
+            parent,
+            points.join(" "),
+            if contour.is_hole { "blue" } else { "red" },
+        ));
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// GeoJSON 线性环：首尾闭合，外环逆时针、内环顺时针（按坐标数值计算）
+fn geojson_ring(points: &[(i32, i32)], counterclockwise: bool) -> String {
+    let area: i64 = (0..points.len())
+        .map(|k| {
+            let a = points[k];
+            let b = points[(k + 1) % points.len()];
+            a.0 as i64 * b.1 as i64 - b.0 as i64 * a.1 as i64
+        })
+        .sum();
+
+    let mut points = points.to_vec();
+    if (area > 0) != counterclockwise {
+        points.reverse();
+    }
+    points.push(points[0]);
+
+    let coordinates: Vec<String> = points
+        .iter()
+        .map(|(x, y)| format!("[{},{}]", x, y))
+        .collect();
+    format!("[{}]", coordinates.join(","))
+}
+
+// 导出为 GeoJSON，外边界为 Polygon，其直接包含的孔洞作为内环，坐标为像素坐标
+pub fn contours_to_geojson(contours: &[Contour]) -> String {
+    let mut features = Vec::new();
+
+    for (k, contour) in contours.iter().enumerate() {
+        if contour.is_hole || contour.points.len() < 3 {
+            continue;
+        }
+
+        let mut rings = vec![geojson_ring(&contour.points, true)];
+        for hole in contours
+            .iter()
+            .filter(|c| c.is_hole && c.parent == Some(k) && c.points.len() >= 3)
+        {
+            rings.push(geojson_ring(&hole.points, false));
+        }
+
+        features.push(format!(
+            "{{\"type\":\"Feature\",\"properties\":{{\"id\":{}}},\"geometry\":{{\"type\":\"Polygon\",\"coordinates\":[{}]}}}}",
+            k,
+            rings.join(",")
+        ));
+    }
+
+    format!(
+        "{{\"type\":\"FeatureCollection\",\"features\":[{}]}}\n",
+        features.join(",")
+    )
+}
+
+// 导出轮廓到文件，按扩展名选择 SVG 或 GeoJSON
+pub fn save_contours(
+    contours: &[Contour],
+    width: u32,
+    height: u32,
+    file: &str,
+) -> Result<(), String> {
+    let text = if file.to_lowercase().ends_with(".svg") {
+        contours_to_svg(contours, width, height)
+    } else {
+        contours_to_geojson(contours)
+    };
+
+    let mut f = File::create(file).map_err(|e| format!("无法创建文件：{}", e))?;
+    f.write_all(text.as_bytes())
+        .map_err(|e| format!("无法写入文件：{}", e))
+}
@@ -0,0 +1,338 @@
+use image::{DynamicImage, GrayImage};
+
+use super::image_util::{convolve_channel, image_to_gray32f, BorderMode, Kernel};
+
+// 梯度算子
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GradientOperator {
+    Sobel,
+    Prewitt,
+    Scharr,
+    Roberts,
+}
+
+impl GradientOperator {
+    // 水平、垂直方向的卷积核
+    pub fn kernels(&self) -> (Kernel, Kernel) {
+        match self {
+            GradientOperator::Sobel => (Kernel::sobel_x(), Kernel::sobel_y()),
+            GradientOperator::Prewitt => (Kernel::prewitt_x(), Kernel::prewitt_y()),
+            GradientOperator::Scharr => (
+                Kernel::separable(vec![-1.0, 0.0, 1.0], vec![3.0, 10.0, 3.0]),
+                Kernel::separable(vec![3.0, 10.0, 3.0], vec![-1.0, 0.0, 1.0]),
+            ),
+            // Roberts 交叉算子为2x2，放入3x3卷积核的右下角
+            GradientOperator::Roberts => (
+                Kernel::new(3, 3, vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, -1.0]),
+                Kernel::new(3, 3, vec![0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0]),
+            ),
+        }
+    }
+}
+
+// 图像梯度，方向为弧度，取值范围 -π~π
+pub struct Gradient {
+    pub width: usize,
+    pub height: usize,
+    pub magnitude: Vec<f32>,
+    pub direction: Vec<f32>,
+}
+
+// 读取 0~255 范围的浮点灰度，16位图像不损失精度
+fn gray_channel(image: &DynamicImage) -> Vec<f32> {
+    image_to_gray32f(image)
+        .into_iter()
+        .map(|v| v * 255.0)
+        .collect()
+}
+
+// 按 sigma 计算高斯核尺寸
+fn gaussian_size(sigma: f32) -> usize {
+    2 * (3.0 * sigma).ceil().max(1.0) as usize + 1
+}
+
+// 将浮点数据线性拉伸到 0~255
+fn normalize_to_gray(data: &[f32], width: usize, height: usize) -> GrayImage {
+    let max = data.iter().fold(0.0_f32, |max, v| max.max(*v));
+    let buffer: Vec<u8> = data
+        .iter()
+        .map(|v| {
+            if max > 0.0 {
+                (v / max * 255.0).round() as u8
+            } else {
+                0
+            }
+        })
+        .collect();
+
+    GrayImage::from_vec(width as u32, height as u32, buffer).unwrap()
+}
+
+// 计算梯度幅值和方向
+pub fn gradient_channel(
+    channel: &[f32],
+    width: usize,
+    height: usize,
+    operator: GradientOperator,
+    border: BorderMode,
+) -> Gradient {
+    let (kernel_x, kernel_y) = operator.kernels();
+    let gx = convolve_channel(channel, width, height, &kernel_x, border);
+    let gy = convolve_channel(channel, width, height, &kernel_y, border);
+
+    let magnitude = gx.iter().zip(&gy).map(|(x, y)| x.hypot(*y)).collect();
+    let direction = gx.iter().zip(&gy).map(|(x, y)| y.atan2(*x)).collect();
+
+    Gradient {
+        width,
+        height,
+        magnitude,
+        direction,
+    }
+}
+
+// 图像梯度
+pub fn gradient(image: &DynamicImage, operator: GradientOperator, border: BorderMode) -> Gradient {
+    let width = image.width() as usize;
+    let height = image.height() as usize;
+
+    gradient_channel(&gray_channel(image), width, height, operator, border)
+}
+
+// 梯度幅值图，按最大值拉伸到 0~255
+pub fn gradient_magnitude_image(
+    image: &DynamicImage,
+    operator: GradientOperator,
+    border: BorderMode,
+) -> GrayImage {
+    let gradient = gradient(image, operator, border);
+    normalize_to_gray(&gradient.magnitude, gradient.width, gradient.height)
+}
+
+// 梯度方向图，-π~π 映射到 0~255
+pub fn gradient_direction_image(
+    image: &DynamicImage,
+    operator: GradientOperator,
+    border: BorderMode,
+) -> GrayImage {
+    let gradient = gradient(image, operator, border);
+    let buffer: Vec<u8> = gradient
+        .direction
+        .iter()
+        .map(|v| ((v + std::f32::consts::PI) / (2.0 * std::f32::consts::PI) * 255.0).round() as u8)
+        .collect();
+
+    GrayImage::from_vec(gradient.width as u32, gradient.height as u32, buffer).unwrap()
+}
+
+// 高斯-拉普拉斯（LoG）卷积核，权值和修正为0
+pub fn log_kernel(sigma: f32) -> Kernel {
+    let size = gaussian_size(sigma);
+    let radius = (size / 2) as i32;
+    let sigma2 = sigma * sigma;
+
+    let mut data = Vec::with_capacity(size * size);
+    for y in -radius..=radius {
+        for x in -radius..=radius {
+            let r2 = (x * x + y * y) as f32;
+            data.push((r2 - 2.0 * sigma2) / (sigma2 * sigma2) * (-r2 / (2.0 * sigma2)).exp());
+        }
+    }
+
+    let mean = data.iter().sum::<f32>() / data.len() as f32;
+    Kernel::new(size, size, data.iter().map(|v| v - mean).collect())
+}
+
+// 高斯-拉普拉斯滤波，返回浮点响应
+pub fn laplacian_of_gaussian(image: &DynamicImage, sigma: f32, border: BorderMode) -> Vec<f32> {
+    let width = image.width() as usize;
+    let height = image.height() as usize;
+
+    convolve_channel(
+        &gray_channel(image),
+        width,
+        height,
+        &log_kernel(sigma),
+        border,
+    )
+}
+
+// 过零点检测，相邻像素符号相反且差值大于 threshold 时记为边缘
+pub fn zero_crossings(response: &[f32], width: usize, height: usize, threshold: f32) -> GrayImage {
+    let mut image = GrayImage::new(width as u32, height as u32);
+
+    for i in 0..height {
+        for j in 0..width {
+            let v = response[i * width + j];
+            let neighbours = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+            for (di, dj) in neighbours {
+                let a = i as i64 + di;
+                let b = j as i64 + dj;
+                if a >= height as i64 || b < 0 || b >= width as i64 {
+                    continue;
+                }
+
+                let u = response[a as usize * width + b as usize];
+                if v * u < 0.0 && (v - u).abs() > threshold {
+                    // 标记绝对值较小的一侧，使边缘更贴近真实过零位置
+                    if v.abs() <= u.abs() {
+                        image.put_pixel(j as u32, i as u32, image::Luma([255]));
+                    } else {
+                        image.put_pixel(b as u32, a as u32, image::Luma([255]));
+                    }
+                }
+            }
+        }
+    }
+
+    image
+}
+
+// LoG 边缘检测
+pub fn log_edges(
+    image: &DynamicImage,
+    sigma: f32,
+    threshold: f32,
+    border: BorderMode,
+) -> GrayImage {
+    let width = image.width() as usize;
+    let height = image.height() as usize;
+
+    let response = laplacian_of_gaussian(image, sigma, border);
+    zero_crossings(&response, width, height, threshold)
+}
+
+// 非极大值抑制，只保留梯度方向上的局部最大值
+fn non_maximum_suppression(gradient: &Gradient) -> Vec<f32> {
+    let width = gradient.width;
+    let height = gradient.height;
+    let magnitude = &gradient.magnitude;
+
+    let get = |i: i64, j: i64| -> f32 {
+        if i < 0 || j < 0 || i >= height as i64 || j >= width as i64 {
+            0.0
+        } else {
+            magnitude[i as usize * width + j as usize]
+        }
+    };
+
+    let mut buffer = vec![0.0_f32; width * height];
+    for i in 0..height {
+        for j in 0..width {
+            let k = i * width + j;
+            let value = magnitude[k];
+            if value == 0.0 {
+                continue;
+            }
+
+            // 方向量化为 0°、45°、90°、135° 四个区间
+            let angle = gradient.direction[k].to_degrees().rem_euclid(180.0);
+            let (di, dj) = if !(22.5..157.5).contains(&angle) {
+                (0, 1)
+            } else if angle < 67.5 {
+                (1, 1)
+            } else if angle < 112.5 {
+                (1, 0)
+            } else {
+                (1, -1)
+            };
+
+            let (i, j) = (i as i64, j as i64);
+            if value >= get(i + di, j + dj) && value >= get(i - di, j - dj) {
+                buffer[k] = value;
+            }
+        }
+    }
+
+    buffer
+}
+
+// 双阈值滞后连接，与强边缘8邻域相连的弱边缘保留
+// 非极大值抑制后为0的像素不是边缘，阈值为0时也不会被选中
+fn hysteresis(data: &[f32], width: usize, height: usize, low: f32, high: f32) -> GrayImage {
+    let mut buffer = vec![0_u8; width * height];
+    let mut stack: Vec<usize> = Vec::new();
+
+    for (k, v) in data.iter().enumerate() {
+        if *v > 0.0 && *v >= high {
+            buffer[k] = 255;
+            stack.push(k);
+        }
+    }
+
+    while let Some(k) = stack.pop() {
+        let i = (k / width) as i64;
+        let j = (k % width) as i64;
+
+        for di in -1..=1 {
+            for dj in -1..=1 {
+                let a = i + di;
+                let b = j + dj;
+                if a < 0 || b < 0 || a >= height as i64 || b >= width as i64 {
+                    continue;
+                }
+
+                let n = a as usize * width + b as usize;
+                if buffer[n] == 0 && data[n] > 0.0 && data[n] >= low {
+                    buffer[n] = 255;
+                    stack.push(n);
+                }
+            }
+        }
+    }
+
+    GrayImage::from_vec(width as u32, height as u32, buffer).unwrap()
+}
+
+// Canny 边缘检测，阈值单位为 Sobel 梯度幅值
+pub fn canny(
+    image: &DynamicImage,
+    sigma: f32,
+    low: f32,
+    high: f32,
+    border: BorderMode,
+) -> GrayImage {
+    let width = image.width() as usize;
+    let height = image.height() as usize;
+
+    let mut channel = gray_channel(image);
+    if sigma > 0.0 {
+        let kernel = Kernel::gaussian(gaussian_size(sigma), sigma);
+        channel = convolve_channel(&channel, width, height, &kernel, border);
+    }
+
+    let gradient = gradient_channel(&channel, width, height, GradientOperator::Sobel, border);
+    let suppressed = non_maximum_suppression(&gradient);
+
+    // 保证低阈值不大于高阈值
+    let (low, high) = (low.min(high), high.max(low));
+    hysteresis(&suppressed, width, height, low, high)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, GrayImage, Luma};
+
+    use super::*;
+
+    // 低阈值为0时单个阶跃边缘不会填满整幅图像
+    #[test]
+    fn canny_zero_low_threshold_keeps_edges_thin() {
+        let image = DynamicImage::ImageLuma8(GrayImage::from_fn(32, 32, |x, _| {
+            Luma([if x < 16 { 0 } else { 255 }])
+        }));
+
+        for (low, high) in [(0.0, 100.0), (0.0, 0.0), (100.0, 0.0)] {
+            let edges = canny(&image, 1.0, low, high, BorderMode::Replicate);
+            let count = edges.pixels().filter(|p| p[0] == 255).count();
+            assert!(
+                count > 0 && count < 32 * 32 / 4,
+                "{} {} {}",
+                low,
+                high,
+                count
+            );
+        }
+    }
+}
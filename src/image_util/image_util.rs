@@ -1,12 +1,18 @@
-use std::collections::HashMap;
 // use std::fs::OpenOptions;
 // use std::io::Write;
 
 use image::io::Reader;
-use image::{DynamicImage, GenericImageView, GrayImage, Pixel, Rgba, RgbaImage};
+use image::{ColorType, DynamicImage, GenericImageView, GrayImage, ImageBuffer};
 use num_complex::Complex;
 use rustfft::FftPlanner;
 
+use super::color::{
+    convert_from_rgb, convert_to_rgb, hsl_to_rgb, hsv_to_rgb, image_to_gray16_standard,
+    image_to_gray_standard, linear_to_srgb, rgb_to_gray, rgb_to_hsl, rgb_to_hsv, srgb_to_linear,
+    ColorSpace, GrayStandard,
+};
+use super::lut3d::{Lut3d, LutInterpolation};
+
 // 读取图片
 pub fn reading_image(path: &str) -> DynamicImage {
     let image = match Reader::open(path) {
@@ -27,100 +33,429 @@ pub fn reading_image(path: &str) -> DynamicImage {
     image
 }
 
-// 图像灰度变换
+// 图像灰度变换，按 BT.601 标准
 pub fn image_to_gray(image: &DynamicImage) -> Vec<u8> {
-    let image_luma: Vec<u8> = image
+    image_to_gray_standard(image, GrayStandard::Bt601)
+}
+
+// 判断图像是否为16位或浮点图像
+pub fn is_high_depth(image: &DynamicImage) -> bool {
+    let color = image.color();
+    color.bytes_per_pixel() / color.channel_count() > 1
+}
+
+// 16位图像灰度变换
+pub fn image_to_gray16(image: &DynamicImage) -> Vec<u16> {
+    image_to_gray16_standard(image, GrayStandard::Bt601)
+}
+
+// 浮点图像灰度变换，取值范围为 0.0~1.0
+pub fn image_to_gray32f(image: &DynamicImage) -> Vec<f32> {
+    if !image.color().has_color() {
+        return image.to_luma32f().into_raw();
+    }
+
+    image
+        .to_rgb32f()
         .pixels()
-        .map(|(_, _, pixel)| {
-            let r = pixel[0] as f32;
-            let g = pixel[1] as f32;
-            let b = pixel[2] as f32;
-
-            let gray = 0.299 * r + 0.587 * g + 0.114 * b;
-            let gray = gray.round() as u8;
-            gray
+        .map(|pixel| rgb_to_gray([pixel[0], pixel[1], pixel[2]], GrayStandard::Bt601))
+        .collect()
+}
+
+// 灰度直方图
+pub fn gray_histogram(gray: &[u8]) -> [u32; 256] {
+    let mut histogram = [0_u32; 256];
+    for v in gray {
+        histogram[*v as usize] += 1;
+    }
+
+    histogram
+}
+
+// 累积分布函数，取值范围 0.0~1.0
+pub fn cumulative_distribution(histogram: &[f64]) -> Vec<f64> {
+    let n: f64 = histogram.iter().sum();
+
+    let mut sum = 0.0;
+    histogram
+        .iter()
+        .map(|v| {
+            sum += v;
+            if n > 0.0 {
+                sum / n
+            } else {
+                0.0
+            }
         })
-        .collect();
+        .collect()
+}
+
+// 直方图均衡化映射表，灰度级数为直方图长度
+pub fn equalization_map(histogram: &[u32]) -> Vec<u32> {
+    let histogram: Vec<f64> = histogram.iter().map(|v| *v as f64).collect();
+    let max_level = histogram.len().saturating_sub(1) as f64;
 
-    image_luma
+    cumulative_distribution(&histogram)
+        .into_iter()
+        .map(|v| (v * max_level + 0.5).floor() as u32)
+        .collect()
+}
+
+// 直方图规定化映射表，将 source 的累积分布映射到 target 上累积分布不小于它的最小灰度级
+// target 为任意非负权重，长度与 source 不同时按比例重采样
+pub fn histogram_matching_map(source: &[u32], target: &[f64]) -> Vec<u32> {
+    let levels = source.len();
+    let target: Vec<f64> = if target.len() == levels {
+        target.to_vec()
+    } else {
+        (0..levels)
+            .map(|v| target[v * target.len() / levels.max(1)])
+            .collect()
+    };
+
+    let source: Vec<f64> = source.iter().map(|v| *v as f64).collect();
+    let source = cumulative_distribution(&source);
+    let target = cumulative_distribution(&target);
+
+    let mut level = 0;
+    source
+        .iter()
+        .map(|v| {
+            // 两个累积分布都单调不减
+            while level + 1 < levels && target[level] < v - 1e-9 {
+                level += 1;
+            }
+            level as u32
+        })
+        .collect()
+}
+
+// 单通道直方图均衡化
+pub fn equalize_channel(channel: &[u8]) -> Vec<u8> {
+    let map = equalization_map(&gray_histogram(channel));
+    channel.iter().map(|v| map[*v as usize] as u8).collect()
 }
 
 // 直方图均值化处理
 pub fn image_gray_average(image: &DynamicImage) -> Vec<u8> {
-    let image_luma = image_to_gray(&image);
+    equalize_channel(&image_to_gray(image))
+}
+
+// 16位直方图均值化处理，共65536级灰度
+pub fn image_gray_average16(image: &DynamicImage) -> Vec<u16> {
+    let image_luma = image_to_gray16(image);
 
-    let mut gray_data: HashMap<u8, i32> = HashMap::new();
+    let mut histogram = vec![0_u32; 65536];
     for v in &image_luma {
-        gray_data
-            .entry(*v)
-            .and_modify(|counter| *counter += 1)
-            .or_insert(1);
-    }
-
-    let mut gray_data: Vec<(u8, i32)> = gray_data.iter().map(|(k, v)| (*k, *v)).collect();
-    gray_data.sort_by(|a, b| a.0.cmp(&b.0));
-
-    // // 输出灰度数据
-    // for i in &gray_data {
-    //     let mut f = OpenOptions::new()
-    //         .write(true)
-    //         .create(true)
-    //         .append(true)
-    //         .open("gray_data.txt")
-    //         .unwrap();
-
-    //     f.write_all(format!("({} {})\n", i.0, i.1).as_bytes())
-    //         .unwrap();
-    // }
-
-    let mut sum: f32 = 0.0;
-    let mut temp: f32 = 0.0;
-    let n = image.dimensions().0 * image.dimensions().1;
-    let gray_data: Vec<(u8, u8)> = gray_data
-        .iter_mut()
-        .map(|(k, v)| {
-            let v = *v as f32;
-            let n = n as f32;
-            sum as f32;
-            sum = temp;
-            sum = sum + v / n;
-            temp = sum;
-
-            sum = (sum * 255.0 + 0.5).floor();
-            let sum = sum as u8;
-            (*k, sum)
-        })
-        .collect();
+        histogram[*v as usize] += 1;
+    }
 
-    // // 输出灰度均值化数据
-    // for i in &gray_data {
-    //     let mut f = OpenOptions::new()
-    //         .write(true)
-    //         .create(true)
-    //         .append(true)
-    //         .open("gray_average_data.txt")
-    //         .unwrap();
+    let map = equalization_map(&histogram);
+    image_luma.iter().map(|v| map[*v as usize] as u16).collect()
+}
 
-    //     f.write_all(format!("({} {})\n", i.0, i.1).as_bytes())
-    //         .unwrap();
-    // }
+// 彩色图像直方图均衡化方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EqualizeMode {
+    // 只均衡 HSV 的明度 V
+    Hsv,
+    // 只均衡 Lab 的亮度 L
+    Lab,
+    // 只均衡 YCbCr 的亮度 Y
+    YCbCr,
+    // RGB 各通道分别均衡，色调可能改变
+    Rgb,
+}
 
-    let map: HashMap<_, _> = gray_data.into_iter().collect();
+// 处理方式对应的颜色空间、需要映射的通道与通道取值上限
+fn mode_channels(mode: EqualizeMode) -> (ColorSpace, &'static [usize], f32) {
+    match mode {
+        EqualizeMode::Hsv => (ColorSpace::Hsv, &[2], 1.0),
+        EqualizeMode::Lab => (ColorSpace::Lab, &[0], 100.0),
+        EqualizeMode::YCbCr => (ColorSpace::YCbCr601, &[0], 1.0),
+        EqualizeMode::Rgb => (ColorSpace::Rgb, &[0, 1, 2], 1.0),
+    }
+}
+
+// 16位图像按65536级处理，否则按256级
+fn image_levels(image: &DynamicImage) -> usize {
+    if is_high_depth(image) {
+        65536
+    } else {
+        256
+    }
+}
 
-    let image_luma: Vec<u8> = image_luma
+fn color_values(image: &DynamicImage, space: ColorSpace) -> Vec<[f32; 4]> {
+    image
+        .to_rgba32f()
+        .pixels()
+        .map(|p| convert_from_rgb([p[0], p[1], p[2]], space))
+        .collect()
+}
+
+fn quantize_channel(colors: &[[f32; 4]], channel: usize, scale: f32, levels: usize) -> Vec<u32> {
+    let max_level = (levels - 1) as f32;
+    colors
         .iter()
-        .map(|v| {
-            let v = map.get_key_value(v).unwrap().1.clone();
-            v
+        .map(|c| {
+            (c[channel] / scale * max_level)
+                .round()
+                .clamp(0.0, max_level) as u32
         })
+        .collect()
+}
+
+// 在指定颜色空间中逐通道做灰度映射后转换回原颜色类型，保留透明通道
+// 通道值按取值上限归一化后量化为 levels 级，f 的参数为通道序号、量化值与级数
+pub fn map_color_channels<F>(image: &DynamicImage, mode: EqualizeMode, f: F) -> DynamicImage
+where
+    F: Fn(usize, &[u32], usize) -> Vec<u32>,
+{
+    let (space, channels, scale) = mode_channels(mode);
+    let levels = image_levels(image);
+    let max_level = (levels - 1) as f32;
+
+    let mut buffer = image.to_rgba32f();
+    let mut colors = color_values(image, space);
+
+    for channel in channels {
+        let quantized = quantize_channel(&colors, *channel, scale, levels);
+        for (color, v) in colors.iter_mut().zip(f(*channel, &quantized, levels)) {
+            color[*channel] = v as f32 / max_level * scale;
+        }
+    }
+
+    for (pixel, color) in buffer.pixels_mut().zip(colors.iter()) {
+        let [r, g, b] = convert_to_rgb(*color, space);
+        pixel[0] = r;
+        pixel[1] = g;
+        pixel[2] = b;
+    }
+
+    convert_color(DynamicImage::ImageRgba32F(buffer), image.color())
+}
+
+// 彩色图像直方图均衡化，保留原图颜色类型与透明通道
+// 亮度方式只均衡亮度通道后转换回 RGB
+pub fn equalize_color_image(image: &DynamicImage, mode: EqualizeMode) -> DynamicImage {
+    // 灰度图各方式结果相同，8位灰度图直接逐通道均衡
+    if !image.color().has_color() && !is_high_depth(image) {
+        return map_channels(image, false, equalize_channel);
+    }
+
+    let mode = if image.color().has_color() {
+        mode
+    } else {
+        EqualizeMode::YCbCr
+    };
+
+    map_color_channels(image, mode, |_, values, levels| {
+        let mut histogram = vec![0_u32; levels];
+        for v in values {
+            histogram[*v as usize] += 1;
+        }
+
+        let map = equalization_map(&histogram);
+        values.iter().map(|v| map[*v as usize]).collect()
+    })
+}
+
+// 裁剪直方图，超出上限的部分平均分配到所有灰度级
+fn clip_histogram(histogram: &mut [u32], limit: u32) {
+    let mut excess = 0_u64;
+    for v in histogram.iter_mut() {
+        if *v > limit {
+            excess += (*v - limit) as u64;
+            *v = limit;
+        }
+    }
+
+    let levels = histogram.len() as u64;
+    let increment = (excess / levels) as u32;
+    let residual = (excess % levels) as usize;
+    for v in histogram.iter_mut() {
+        *v += increment;
+    }
+
+    // 余数等间隔分配
+    if let Some(step) = histogram.len().checked_div(residual) {
+        for v in histogram.iter_mut().step_by(step.max(1)).take(residual) {
+            *v += 1;
+        }
+    }
+}
+
+// 限制对比度自适应直方图均衡化（CLAHE）
+// 图像分为 tiles 个子块，各子块直方图按 clip_limit 倍平均值裁剪后计算映射表，像素值由相邻四个子块的映射双线性插值
+pub fn clahe_channel(
+    values: &[u32],
+    width: usize,
+    height: usize,
+    levels: usize,
+    tiles: (usize, usize),
+    clip_limit: f32,
+) -> Vec<u32> {
+    let tiles_x = tiles.0.clamp(1, width.max(1));
+    let tiles_y = tiles.1.clamp(1, height.max(1));
+    let tile_range = |index: usize, count: usize, size: usize| {
+        (index * size / count, (index + 1) * size / count)
+    };
+
+    // 各子块映射表
+    let mut maps = Vec::with_capacity(tiles_x * tiles_y);
+    for ty in 0..tiles_y {
+        let (y0, y1) = tile_range(ty, tiles_y, height);
+        for tx in 0..tiles_x {
+            let (x0, x1) = tile_range(tx, tiles_x, width);
+
+            let mut histogram = vec![0_u32; levels];
+            for y in y0..y1 {
+                for v in &values[y * width + x0..y * width + x1] {
+                    histogram[*v as usize] += 1;
+                }
+            }
+
+            if clip_limit > 0.0 {
+                let area = ((x1 - x0) * (y1 - y0)) as f32;
+                let limit = (clip_limit * area / levels as f32).max(1.0) as u32;
+                clip_histogram(&mut histogram, limit);
+            }
+
+            maps.push(equalization_map(&histogram));
+        }
+    }
+
+    // 像素相对子块中心的位置，返回相邻两个子块与插值权重
+    let neighbors = |v: usize, count: usize, size: usize| {
+        let t =
+            ((v as f32 + 0.5) * count as f32 / size as f32 - 0.5).clamp(0.0, (count - 1) as f32);
+        let i0 = t.floor() as usize;
+        let i1 = (i0 + 1).min(count - 1);
+        (i0, i1, t - i0 as f32)
+    };
+
+    let mut result = Vec::with_capacity(values.len());
+    for y in 0..height {
+        let (ty0, ty1, wy) = neighbors(y, tiles_y, height);
+        for x in 0..width {
+            let (tx0, tx1, wx) = neighbors(x, tiles_x, width);
+            let v = values[y * width + x] as usize;
+
+            let top = maps[ty0 * tiles_x + tx0][v] as f32 * (1.0 - wx)
+                + maps[ty0 * tiles_x + tx1][v] as f32 * wx;
+            let bottom = maps[ty1 * tiles_x + tx0][v] as f32 * (1.0 - wx)
+                + maps[ty1 * tiles_x + tx1][v] as f32 * wx;
+            result.push((top * (1.0 - wy) + bottom * wy).round() as u32);
+        }
+    }
+
+    result
+}
+
+// 灰度图像 CLAHE
+pub fn clahe_gray_image(image: &DynamicImage, tiles: (usize, usize), clip_limit: f32) -> GrayImage {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let gray: Vec<u32> = image_to_gray(image).into_iter().map(|v| v as u32).collect();
+    let buffer: Vec<u8> = clahe_channel(&gray, width, height, 256, tiles, clip_limit)
+        .into_iter()
+        .map(|v| v as u8)
         .collect();
 
-    image_luma
+    GrayImage::from_vec(width as u32, height as u32, buffer).unwrap()
+}
+
+// 彩色图像 CLAHE，亮度方式只处理亮度通道，灰度图按亮度处理
+pub fn clahe_color_image(
+    image: &DynamicImage,
+    tiles: (usize, usize),
+    clip_limit: f32,
+    mode: EqualizeMode,
+) -> DynamicImage {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let mode = if image.color().has_color() {
+        mode
+    } else {
+        EqualizeMode::YCbCr
+    };
+
+    map_color_channels(image, mode, |_, values, levels| {
+        clahe_channel(values, width, height, levels, tiles, clip_limit)
+    })
+}
+
+// 直方图匹配到参考图像，按处理方式匹配亮度或 RGB 各通道，灰度图按亮度匹配
+pub fn match_histogram(
+    image: &DynamicImage,
+    reference: &DynamicImage,
+    mode: EqualizeMode,
+) -> DynamicImage {
+    let mode = if image.color().has_color() {
+        mode
+    } else {
+        EqualizeMode::YCbCr
+    };
+    let (space, _, scale) = mode_channels(mode);
+    let references = color_values(reference, space);
+
+    map_color_channels(image, mode, |channel, values, levels| {
+        let mut source = vec![0_u32; levels];
+        for v in values {
+            source[*v as usize] += 1;
+        }
+
+        let mut target = vec![0.0_f64; levels];
+        for v in quantize_channel(&references, channel, scale, levels) {
+            target[v as usize] += 1.0;
+        }
+
+        let map = histogram_matching_map(&source, &target);
+        values.iter().map(|v| map[*v as usize]).collect()
+    })
 }
 
-// 图像输出
-pub fn image_output(image: &DynamicImage, file: &str) {
-    image.save(file).unwrap();
+// 直方图匹配到给定的目标分布，target 为各灰度级的相对权重
+pub fn match_distribution(
+    image: &DynamicImage,
+    target: &[f64],
+    mode: EqualizeMode,
+) -> DynamicImage {
+    let mode = if image.color().has_color() {
+        mode
+    } else {
+        EqualizeMode::YCbCr
+    };
+
+    map_color_channels(image, mode, |_, values, levels| {
+        let mut source = vec![0_u32; levels];
+        for v in values {
+            source[*v as usize] += 1;
+        }
+
+        let map = histogram_matching_map(&source, target);
+        values.iter().map(|v| map[*v as usize]).collect()
+    })
+}
+
+// 图像输出，PNG 不支持浮点格式，浮点图像转换为 16 位后保存
+pub fn image_output(image: &DynamicImage, file: &str) -> Result<(), String> {
+    let png = file.to_lowercase().ends_with(".png");
+    let converted;
+    let image = match image.color() {
+        ColorType::Rgb32F if png => {
+            converted = DynamicImage::ImageRgb16(image.to_rgb16());
+            &converted
+        }
+        ColorType::Rgba32F if png => {
+            converted = DynamicImage::ImageRgba16(image.to_rgba16());
+            &converted
+        }
+        _ => image,
+    };
+
+    image
+        .save(file)
+        .map_err(|e| format!("保存{}失败：{}", file, e))
 }
 
 // // 获取图片RGBA数组并写入txt文件
@@ -167,12 +502,164 @@ pub fn gray_linear_transfromationg(
     new_image_luma
 }
 
+// 16位灰度线性变换，gray_level 最大为65535
+pub fn gray_linear_transfromationg16(
+    image: &DynamicImage,
+    a: f32,
+    b: f32,
+    gray_level: u16,
+) -> Vec<u16> {
+    let gray_level = gray_level as f32;
+
+    image_to_gray16(image)
+        .iter()
+        .map(|v| {
+            let gray = a * *v as f32 + b;
+            gray.clamp(0.0, gray_level) as u16
+        })
+        .collect()
+}
+
+// 曲线查找表，控制点为 0~255 的 (输入, 输出)
+// 采用 Fritsch-Carlson 单调三次插值，相邻控制点之间不会出现过冲，两端之外保持端点输出
+pub fn curve_lut(points: &[(f32, f32)]) -> Vec<u8> {
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    points.dedup_by(|a, b| a.0 == b.0);
+
+    let n = points.len();
+    if n == 0 {
+        return (0..=255).collect();
+    }
+
+    // 各段斜率与控制点处的切线
+    let secants: Vec<f32> = points
+        .windows(2)
+        .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+        .collect();
+    let mut tangents = vec![0.0_f32; n];
+    if n > 1 {
+        tangents[0] = secants[0];
+        tangents[n - 1] = secants[n - 2];
+        for k in 1..n - 1 {
+            if secants[k - 1] * secants[k] > 0.0 {
+                tangents[k] = (secants[k - 1] + secants[k]) / 2.0;
+            }
+        }
+
+        // 限制切线长度以保持单调
+        for (k, d) in secants.iter().enumerate() {
+            if *d == 0.0 {
+                tangents[k] = 0.0;
+                tangents[k + 1] = 0.0;
+                continue;
+            }
+
+            let (a, b) = (tangents[k] / d, tangents[k + 1] / d);
+            let s = a * a + b * b;
+            if s > 9.0 {
+                let t = 3.0 / s.sqrt();
+                tangents[k] = t * a * d;
+                tangents[k + 1] = t * b * d;
+            }
+        }
+    }
+
+    (0..256)
+        .map(|v| {
+            let x = v as f32;
+            let y = if x <= points[0].0 {
+                points[0].1
+            } else if x >= points[n - 1].0 {
+                points[n - 1].1
+            } else {
+                let k = points.iter().position(|p| p.0 > x).unwrap() - 1;
+                let ((x0, y0), (x1, y1)) = (points[k], points[k + 1]);
+                let h = x1 - x0;
+                let t = (x - x0) / h;
+                let (t2, t3) = (t * t, t * t * t);
+
+                (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+                    + (t3 - 2.0 * t2 + t) * h * tangents[k]
+                    + (-2.0 * t3 + 3.0 * t2) * y1
+                    + (t3 - t2) * h * tangents[k + 1]
+            };
+
+            y.round().clamp(0.0, 255.0) as u8
+        })
+        .collect()
+}
+
+// 色阶查找表：输入 [black, white] 经 gamma 校正后映射到输出 [out_black, out_white]
+pub fn levels_lut(input: (u8, u8), gamma: f32, output: (u8, u8)) -> Vec<u8> {
+    let (black, white) = (input.0 as f32, input.1 as f32);
+    let (out_black, out_white) = (output.0 as f32, output.1 as f32);
+
+    (0..256)
+        .map(|v| {
+            let r = if white > black {
+                ((v as f32 - black) / (white - black)).clamp(0.0, 1.0)
+            } else if v as f32 >= black {
+                1.0
+            } else {
+                0.0
+            };
+            let r = r.powf(1.0 / gamma.max(0.01));
+
+            (out_black + r * (out_white - out_black))
+                .round()
+                .clamp(0.0, 255.0) as u8
+        })
+        .collect()
+}
+
+// 查找表处理，channel_luts 依次作用于 R、G、B 通道，之后 master 作用于所有颜色通道
+// 灰度图只使用 master，透明通道不变，保留原图颜色类型
+// 16位及浮点图像在查找表相邻项之间线性插值，不损失精度
+pub fn apply_luts(image: &DynamicImage, master: &[u8], channel_luts: &[Vec<u8>]) -> DynamicImage {
+    let color = image.color();
+    let color_count = if color.has_color() { 3 } else { 1 };
+    let lookup = |lut: &[u8], v: f32| {
+        let v = v.clamp(0.0, 255.0);
+        let index = v.floor() as usize;
+        let next = (index + 1).min(255);
+        let t = v - index as f32;
+        lut[index] as f32 * (1.0 - t) + lut[next] as f32 * t
+    };
+
+    let channels: Vec<Vec<f32>> = split_channels_f32(image)
+        .into_iter()
+        .enumerate()
+        .map(|(c, channel)| {
+            if c >= color_count {
+                return channel;
+            }
+
+            let lut = channel_luts.get(c).filter(|_| color.has_color());
+            channel
+                .iter()
+                .map(|v| {
+                    let v = match lut {
+                        Some(lut) => lookup(lut, *v),
+                        None => *v,
+                    };
+                    lookup(master, v)
+                })
+                .collect()
+        })
+        .collect();
+
+    merge_channels_f32(&channels, image.width(), image.height(), color)
+}
+
 // 灰度图快速傅里叶变换
 pub fn gray_fast_fourier_transform(image: &DynamicImage) -> GrayImage {
     let width = image.width() as usize;
     let height = image.height() as usize;
-    let gray_image = image_to_gray(image);
-    let gray_image: Vec<f64> = gray_image.into_iter().map(|x| x as f64).collect();
+
+    // 以浮点数读取灰度，16位及浮点图像不损失精度
+    let gray_image = image_to_gray32f(image);
+    let gray_image: Vec<f64> = gray_image.into_iter().map(|x| x as f64 * 255.0).collect();
 
     let mut buffer: Vec<Vec<Complex<f64>>> = vec![vec![Complex::new(0.0, 0.0); width]; height];
     for (i, v) in gray_image.into_iter().enumerate() {
@@ -235,10 +722,229 @@ pub fn adjust_contrast_image(image: &DynamicImage, contrast: f32) -> DynamicImag
     new_image
 }
 
-// 图像亮度变换
+// 图像亮度变换，brightness 为原位深下的增量，8位图像满量程为255，16位及浮点图像为65535
 pub fn adjust_brightness_image(image: &DynamicImage, brightness: i32) -> DynamicImage {
-    let new_image = image.brighten(brightness);
-    new_image
+    let max_level = if is_high_depth(image) { 65535.0 } else { 255.0 };
+    adjust_brightness_normalized(image, brightness as f32 / max_level)
+}
+
+// 按满量程的比例调整亮度，结果与位深无关
+pub fn adjust_brightness_normalized(image: &DynamicImage, amount: f32) -> DynamicImage {
+    map_rgb_pixels(image, |rgb| rgb.map(|v| v + amount))
+}
+
+// 逐像素颜色变换，RGB 为 0.0~1.0，保留颜色类型与透明通道
+fn map_rgb_pixels<F: Fn([f32; 3]) -> [f32; 3]>(image: &DynamicImage, f: F) -> DynamicImage {
+    let mut buffer = image.to_rgba32f();
+    for pixel in buffer.pixels_mut() {
+        let rgb = f([pixel[0], pixel[1], pixel[2]]);
+        for (c, v) in rgb.iter().enumerate() {
+            pixel[c] = v.clamp(0.0, 1.0);
+        }
+    }
+
+    convert_color(DynamicImage::ImageRgba32F(buffer), image.color())
+}
+
+// 饱和度调整，factor 为饱和度倍数，space 为 Hsv 时按 HSV 饱和度计算，否则按 HSL
+pub fn adjust_saturation_image(
+    image: &DynamicImage,
+    factor: f32,
+    space: ColorSpace,
+) -> DynamicImage {
+    map_rgb_pixels(image, |rgb| match space {
+        ColorSpace::Hsv => {
+            let [h, s, v] = rgb_to_hsv(rgb);
+            hsv_to_rgb([h, (s * factor).clamp(0.0, 1.0), v])
+        }
+        _ => {
+            let [h, s, l] = rgb_to_hsl(rgb);
+            hsl_to_rgb([h, (s * factor).clamp(0.0, 1.0), l])
+        }
+    })
+}
+
+// 色相旋转，单位为角度
+pub fn rotate_hue_image(image: &DynamicImage, degrees: f32) -> DynamicImage {
+    map_rgb_pixels(image, |rgb| {
+        let [h, s, l] = rgb_to_hsl(rgb);
+        hsl_to_rgb([h + degrees, s, l])
+    })
+}
+
+// 自然饱和度，amount 为 -1.0~1.0，低饱和度的颜色变化大，高饱和度的颜色变化小
+pub fn adjust_vibrance_image(image: &DynamicImage, amount: f32, space: ColorSpace) -> DynamicImage {
+    let vibrance = |s: f32| (s * (1.0 + amount * (1.0 - s))).clamp(0.0, 1.0);
+    map_rgb_pixels(image, |rgb| match space {
+        ColorSpace::Hsv => {
+            let [h, s, v] = rgb_to_hsv(rgb);
+            hsv_to_rgb([h, vibrance(s), v])
+        }
+        _ => {
+            let [h, s, l] = rgb_to_hsl(rgb);
+            hsl_to_rgb([h, vibrance(s), l])
+        }
+    })
+}
+
+// 通道混合器，每行为一个输出通道对输入 R、G、B 的权重
+// 单色模式下只使用第一行的权重，三个通道输出相同的灰度
+pub fn channel_mixer_image(
+    image: &DynamicImage,
+    mixer: [[f32; 3]; 3],
+    monochrome: bool,
+) -> DynamicImage {
+    let mix = |row: [f32; 3], rgb: [f32; 3]| row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+    map_rgb_pixels(image, |rgb| {
+        if monochrome {
+            [mix(mixer[0], rgb); 3]
+        } else {
+            mixer.map(|row| mix(row, rgb))
+        }
+    })
+}
+
+// 3D LUT 颜色变换，保留颜色类型与透明通道
+pub fn lut3d_image(
+    image: &DynamicImage,
+    lut: &Lut3d,
+    interpolation: LutInterpolation,
+) -> DynamicImage {
+    map_rgb_pixels(image, |rgb| lut.lookup(rgb, interpolation))
+}
+
+// 自动白平衡方法
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WhiteBalance {
+    // 灰度世界：假设场景平均为中性灰
+    GrayWorld,
+    // 白块（最大 RGB）：假设最亮处为白色
+    WhitePatch,
+    // 以各通道的百分位数作为白点，比白块更不易受少量高光噪点影响
+    Percentile(f32),
+}
+
+// 由各通道的参考值计算增益，绿色通道增益为 1
+fn gains_from_white(white: [f32; 3]) -> [f32; 3] {
+    white.map(|v| if v > 0.0 { white[1] / v } else { 1.0 })
+}
+
+// 线性光下的 RGB 各通道数值
+fn linear_channels(image: &DynamicImage) -> [Vec<f32>; 3] {
+    let buffer = image.to_rgb32f();
+    let mut channels: [Vec<f32>; 3] = Default::default();
+    for pixel in buffer.pixels() {
+        for (c, channel) in channels.iter_mut().enumerate() {
+            channel.push(srgb_to_linear(pixel[c]));
+        }
+    }
+    channels
+}
+
+// 自动白平衡增益，在线性光空间中计算
+pub fn white_balance_gains(image: &DynamicImage, method: WhiteBalance) -> [f32; 3] {
+    let mut channels = linear_channels(image);
+    if channels[0].is_empty() {
+        return [1.0; 3];
+    }
+
+    let percentile = match method {
+        WhiteBalance::GrayWorld => {
+            let white = channels.map(|channel| channel.iter().sum::<f32>() / channel.len() as f32);
+            return gains_from_white(white);
+        }
+        WhiteBalance::WhitePatch => 100.0,
+        WhiteBalance::Percentile(p) => p.clamp(0.0, 100.0),
+    };
+
+    let mut white = [0.0; 3];
+    for (v, channel) in white.iter_mut().zip(channels.iter_mut()) {
+        let index = ((channel.len() - 1) as f32 * percentile / 100.0).round() as usize;
+        *v = *channel
+            .select_nth_unstable_by(index, |a, b| a.total_cmp(b))
+            .1;
+    }
+    gains_from_white(white)
+}
+
+// 以指定像素邻域为中性色计算白平衡增益
+pub fn neutral_point_gains(image: &DynamicImage, (x, y): (u32, u32), radius: u32) -> [f32; 3] {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return [1.0; 3];
+    }
+
+    let buffer = image.to_rgb32f();
+    let mut white = [0.0; 3];
+    for j in y.saturating_sub(radius)..(y + radius + 1).min(height) {
+        for i in x.saturating_sub(radius)..(x + radius + 1).min(width) {
+            let pixel = buffer.get_pixel(i, j);
+            for (c, v) in white.iter_mut().enumerate() {
+                *v += srgb_to_linear(pixel[c]);
+            }
+        }
+    }
+    gains_from_white(white)
+}
+
+// 黑体辐射在给定色温（K）下的颜色（sRGB，0.0~1.0），Tanner Helland 近似
+fn kelvin_to_rgb(kelvin: f32) -> [f32; 3] {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.69873 * (t - 60.0).powf(-0.13320476)
+    };
+    let g = if t <= 66.0 {
+        99.4708 * t.ln() - 161.11957
+    } else {
+        288.12216 * (t - 60.0).powf(-0.07551485)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.51773 * (t - 10.0).ln() - 305.0448
+    };
+
+    [r, g, b].map(|v| v.clamp(0.0, 255.0) / 255.0)
+}
+
+// 色温与色调增益，temperature 为拍摄光源色温（6500K 时不变），tint 为 -100~100，正值偏品红
+pub fn temperature_tint_gains(temperature: f32, tint: f32) -> [f32; 3] {
+    let light = kelvin_to_rgb(temperature).map(srgb_to_linear);
+    let reference = kelvin_to_rgb(6500.0).map(srgb_to_linear);
+    let gains: [f32; 3] = std::array::from_fn(|c| reference[c] / light[c].max(1e-3));
+
+    [
+        gains[0] / gains[1],
+        1.0 - 0.3 * tint.clamp(-100.0, 100.0) / 100.0,
+        gains[2] / gains[1],
+    ]
+}
+
+// 3×3 颜色矩阵变换，在线性光空间中进行，保留颜色类型与透明通道
+pub fn color_matrix_image(image: &DynamicImage, matrix: [[f32; 3]; 3]) -> DynamicImage {
+    map_rgb_pixels(image, |rgb| {
+        let rgb = rgb.map(srgb_to_linear);
+        matrix.map(|row| {
+            let v = row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+            linear_to_srgb(v.clamp(0.0, 1.0))
+        })
+    })
+}
+
+// 按增益缩放各通道实现白平衡
+pub fn white_balance_image(image: &DynamicImage, gains: [f32; 3]) -> DynamicImage {
+    color_matrix_image(
+        image,
+        [
+            [gains[0], 0.0, 0.0],
+            [0.0, gains[1], 0.0],
+            [0.0, 0.0, gains[2]],
+        ],
+    )
 }
 
 // 图像高斯模糊
@@ -247,132 +953,901 @@ pub fn blur_image(image: &DynamicImage, sigma: f32) -> DynamicImage {
     new_image
 }
 
-// 图像平滑
-pub fn smooth_image(image: &DynamicImage, radius: u32) -> DynamicImage {
-    let width = image.width();
-    let height = image.height();
+// 邻域运算的边界处理方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BorderMode {
+    // 以常数填充
+    Constant(u8),
+    // 复制边缘像素 aaa|abc|ccc
+    Replicate,
+    // 镜像反射 cba|abc|cba
+    Reflect,
+    // 周期环绕 abc|abc|abc
+    Wrap,
+}
 
-    let mut buffer: Vec<Vec<Rgba<u8>>> =
-        vec![vec![Rgba([0, 0, 0, 0]); width as usize]; height as usize];
-    for (_, v) in image.pixels().enumerate() {
-        buffer[v.1 as usize][v.0 as usize] = v.2;
+impl BorderMode {
+    // 将越界坐标映射回 0..len，常数填充时返回 None
+    pub fn map_index(&self, index: i64, len: usize) -> Option<usize> {
+        let len = len as i64;
+        if (0..len).contains(&index) {
+            return Some(index as usize);
+        }
+
+        match self {
+            BorderMode::Constant(_) => None,
+            BorderMode::Replicate => Some(index.clamp(0, len - 1) as usize),
+            BorderMode::Reflect => {
+                // 以 2*len 为周期折返，图像再小也不会越界
+                let index = index.rem_euclid(2 * len);
+                if index < len {
+                    Some(index as usize)
+                } else {
+                    Some((2 * len - 1 - index) as usize)
+                }
+            }
+            BorderMode::Wrap => Some(index.rem_euclid(len) as usize),
+        }
     }
+}
 
-    let mut new_buffer = buffer.clone();
+// 按边界方式向左右各扩展 pad_x 个像素，上下各扩展 pad_y 个像素
+pub fn pad_channel<T: Copy + From<u8>>(
+    channel: &[T],
+    width: usize,
+    height: usize,
+    pad_x: usize,
+    pad_y: usize,
+    border: BorderMode,
+) -> Vec<T> {
+    let new_width = width + 2 * pad_x;
+    let new_height = height + 2 * pad_y;
+
+    let mut buffer = vec![T::from(0); new_width * new_height];
+    for row in 0..new_height {
+        let y = border.map_index(row as i64 - pad_y as i64, height);
+        for col in 0..new_width {
+            let x = border.map_index(col as i64 - pad_x as i64, width);
+            buffer[row * new_width + col] = match (x, y, border) {
+                (Some(x), Some(y), _) => channel[y * width + x],
+                (_, _, BorderMode::Constant(value)) => T::from(value),
+                _ => T::from(0),
+            };
+        }
+    }
+
+    buffer
+}
 
-    for i in radius..height - radius {
-        for j in radius..width - radius {
-            let mut list = Vec::new();
+// 裁去扩展出的边界
+pub fn crop_channel<T: Copy>(
+    channel: &[T],
+    width: usize,
+    height: usize,
+    pad_x: usize,
+    pad_y: usize,
+) -> Vec<T> {
+    let padded_width = width + 2 * pad_x;
+    let mut buffer = Vec::with_capacity(width * height);
+    for row in pad_y..pad_y + height {
+        buffer.extend_from_slice(
+            &channel[row * padded_width + pad_x..row * padded_width + pad_x + width],
+        );
+    }
 
-            for a in i - radius..i + radius + 1 {
-                for b in j - radius..j + radius + 1 {
-                    list.push(buffer[a as usize][b as usize]);
+    buffer
+}
+
+// 矩形窗口局部最大值或最小值滤波，窗口在边界处截断
+// 按行、列分两次处理，单调队列使计算量与窗口大小无关
+pub fn local_extreme_channel(
+    channel: &[u8],
+    width: usize,
+    height: usize,
+    radius_x: usize,
+    radius_y: usize,
+    max: bool,
+) -> Vec<u8> {
+    let better = |a: u8, b: u8| if max { a >= b } else { a <= b };
+
+    let sliding = |line: &[u8], radius: usize, out: &mut Vec<u8>| {
+        let len = line.len();
+        let mut queue: std::collections::VecDeque<usize> = std::collections::VecDeque::new();
+        let mut next = 0;
+        for k in 0..len {
+            // 窗口右边界推进到 k + radius
+            while next < len && next <= k + radius {
+                while let Some(&back) = queue.back() {
+                    if better(line[next], line[back]) {
+                        queue.pop_back();
+                    } else {
+                        break;
+                    }
                 }
+                queue.push_back(next);
+                next += 1;
             }
 
-            let mut red_list = Vec::new();
-            let mut green_list = Vec::new();
-            let mut blue_list = Vec::new();
-
-            for v in list.iter() {
-                red_list.push(v.0[0]);
-                green_list.push(v.0[1]);
-                blue_list.push(v.0[2]);
+            while let Some(&front) = queue.front() {
+                if front + radius < k {
+                    queue.pop_front();
+                } else {
+                    break;
+                }
             }
 
-            red_list.sort();
-            green_list.sort();
-            blue_list.sort();
+            out.push(line[*queue.front().unwrap()]);
+        }
+    };
 
-            let pixel = Rgba([
-                red_list[red_list.len() / 2],
-                green_list[green_list.len() / 2],
-                blue_list[blue_list.len() / 2],
-                255,
-            ]);
+    let mut temp = Vec::with_capacity(width * height);
+    for row in channel.chunks_exact(width) {
+        sliding(row, radius_x, &mut temp);
+    }
 
-            new_buffer[i as usize][j as usize] = pixel;
+    let mut buffer = vec![0_u8; width * height];
+    let mut column = Vec::with_capacity(height);
+    let mut result = Vec::with_capacity(height);
+    for col in 0..width {
+        column.clear();
+        result.clear();
+        column.extend((0..height).map(|row| temp[row * width + col]));
+        sliding(&column, radius_y, &mut result);
+        for (row, v) in result.iter().enumerate() {
+            buffer[row * width + col] = *v;
         }
     }
 
-    let new_buffer: Vec<Rgba<u8>> = new_buffer.into_iter().flatten().collect();
-    let mut image = RgbaImage::new(width, height);
+    buffer
+}
+
+// 将图像拆分为8位通道，通道数与原颜色类型一致
+pub fn split_channels(image: &DynamicImage) -> Vec<Vec<u8>> {
+    let channel_count = image.color().channel_count() as usize;
+    let buffer = match channel_count {
+        1 => image.to_luma8().into_raw(),
+        2 => image.to_luma_alpha8().into_raw(),
+        3 => image.to_rgb8().into_raw(),
+        _ => image.to_rgba8().into_raw(),
+    };
+    let channel_count = channel_count.min(4);
 
-    for (k, v) in image.pixels_mut().enumerate() {
-        *v = new_buffer[k];
+    let mut channels = vec![Vec::with_capacity(buffer.len() / channel_count); channel_count];
+    for pixel in buffer.chunks_exact(channel_count) {
+        for (c, v) in pixel.iter().enumerate() {
+            channels[c].push(*v);
+        }
     }
 
-    let image = image::DynamicImage::ImageRgba8(image);
-    image
+    channels
 }
 
-// 图像锐化
-pub fn sharpen_image(image: &DynamicImage, index: i32) -> DynamicImage {
-    let width = image.width();
-    let height = image.height();
+// 将8位通道重新组合为指定颜色类型的图像
+pub fn merge_channels(
+    channels: &[Vec<u8>],
+    width: u32,
+    height: u32,
+    color: ColorType,
+) -> DynamicImage {
+    let mut buffer = Vec::with_capacity(channels.len() * channels[0].len());
+    for k in 0..channels[0].len() {
+        for channel in channels {
+            buffer.push(channel[k]);
+        }
+    }
+
+    let image = match channels.len() {
+        1 => DynamicImage::ImageLuma8(ImageBuffer::from_raw(width, height, buffer).unwrap()),
+        2 => DynamicImage::ImageLumaA8(ImageBuffer::from_raw(width, height, buffer).unwrap()),
+        3 => DynamicImage::ImageRgb8(ImageBuffer::from_raw(width, height, buffer).unwrap()),
+        _ => DynamicImage::ImageRgba8(ImageBuffer::from_raw(width, height, buffer).unwrap()),
+    };
+
+    convert_color(image, color)
+}
 
-    let mut buffer: Vec<Vec<Rgba<u8>>> =
-        vec![vec![Rgba([0, 0, 0, 0]); width as usize]; height as usize];
-    for (_, v) in image.pixels().enumerate() {
-        buffer[v.1 as usize][v.0 as usize] = v.2;
+// 转换图像颜色类型
+pub fn convert_color(image: DynamicImage, color: ColorType) -> DynamicImage {
+    if image.color() == color {
+        return image;
     }
 
-    let mut new_buffer = buffer.clone();
-    let laplace_filter = [1, 1, 1, 1, -8, 1, 1, 1, 1];
+    match color {
+        ColorType::L8 => DynamicImage::ImageLuma8(image.to_luma8()),
+        ColorType::La8 => DynamicImage::ImageLumaA8(image.to_luma_alpha8()),
+        ColorType::Rgb8 => DynamicImage::ImageRgb8(image.to_rgb8()),
+        ColorType::Rgba8 => DynamicImage::ImageRgba8(image.to_rgba8()),
+        ColorType::L16 => DynamicImage::ImageLuma16(image.to_luma16()),
+        ColorType::La16 => DynamicImage::ImageLumaA16(image.to_luma_alpha16()),
+        ColorType::Rgb16 => DynamicImage::ImageRgb16(image.to_rgb16()),
+        ColorType::Rgba16 => DynamicImage::ImageRgba16(image.to_rgba16()),
+        ColorType::Rgb32F => DynamicImage::ImageRgb32F(image.to_rgb32f()),
+        ColorType::Rgba32F => DynamicImage::ImageRgba32F(image.to_rgba32f()),
+        _ => image,
+    }
+}
+
+// 逐通道处理图像并保持原颜色类型
+// process_alpha 为 false 时透明通道原样保留
+pub fn map_channels<F>(image: &DynamicImage, process_alpha: bool, f: F) -> DynamicImage
+where
+    F: Fn(&[u8]) -> Vec<u8>,
+{
+    let has_alpha = image.color().has_alpha();
+    let channel_count = image.color().channel_count() as usize;
+
+    let channels: Vec<Vec<u8>> = split_channels(image)
+        .into_iter()
+        .enumerate()
+        .map(|(c, channel)| {
+            if has_alpha && c == channel_count - 1 && !process_alpha {
+                channel
+            } else {
+                f(&channel)
+            }
+        })
+        .collect();
+
+    merge_channels(&channels, image.width(), image.height(), image.color())
+}
+
+// 每个通道采样值的字节数，8位为1，16位为2，浮点为4
+fn sample_bytes(color: ColorType) -> u16 {
+    color.bytes_per_pixel() as u16 / color.channel_count().max(1) as u16
+}
+
+// 将图像拆分为浮点通道，数值统一按8位范围（0.0~255.0）表示，16位及浮点图像不损失精度
+pub fn split_channels_f32(image: &DynamicImage) -> Vec<Vec<f32>> {
+    let channel_count = (image.color().channel_count() as usize).min(4);
+    let buffer: Vec<f32> = match (sample_bytes(image.color()), channel_count) {
+        (1, _) => {
+            return split_channels(image)
+                .iter()
+                .map(|channel| channel.iter().map(|v| *v as f32).collect())
+                .collect();
+        }
+        (2, 1) => image
+            .to_luma16()
+            .into_raw()
+            .iter()
+            .map(|v| *v as f32 / 257.0)
+            .collect(),
+        (2, 2) => image
+            .to_luma_alpha16()
+            .into_raw()
+            .iter()
+            .map(|v| *v as f32 / 257.0)
+            .collect(),
+        (2, 3) => image
+            .to_rgb16()
+            .into_raw()
+            .iter()
+            .map(|v| *v as f32 / 257.0)
+            .collect(),
+        (2, _) => image
+            .to_rgba16()
+            .into_raw()
+            .iter()
+            .map(|v| *v as f32 / 257.0)
+            .collect(),
+        (_, 3) => image
+            .to_rgb32f()
+            .into_raw()
+            .iter()
+            .map(|v| v * 255.0)
+            .collect(),
+        _ => image
+            .to_rgba32f()
+            .into_raw()
+            .iter()
+            .map(|v| v * 255.0)
+            .collect(),
+    };
 
-    for i in 1..height - 1 {
-        for j in 1..width - 1 {
-            let mut list = Vec::new();
+    let mut channels = vec![Vec::with_capacity(buffer.len() / channel_count); channel_count];
+    for pixel in buffer.chunks_exact(channel_count) {
+        for (c, v) in pixel.iter().enumerate() {
+            channels[c].push(*v);
+        }
+    }
+
+    channels
+}
+
+// 将 0.0~255.0 范围的浮点通道组合为指定颜色类型的图像，数值截断后按原位深保存
+pub fn merge_channels_f32(
+    channels: &[Vec<f32>],
+    width: u32,
+    height: u32,
+    color: ColorType,
+) -> DynamicImage {
+    let mut buffer = Vec::with_capacity(channels.len() * channels[0].len());
+    for k in 0..channels[0].len() {
+        for channel in channels {
+            buffer.push(channel[k].clamp(0.0, 255.0));
+        }
+    }
 
-            for a in i - 1..i + 2 {
-                for b in j - 1..j + 2 {
-                    list.push(buffer[a as usize][b as usize]);
+    match sample_bytes(color) {
+        1 => {
+            let channels: Vec<Vec<u8>> = channels
+                .iter()
+                .map(|channel| {
+                    channel
+                        .iter()
+                        .map(|v| v.round().clamp(0.0, 255.0) as u8)
+                        .collect()
+                })
+                .collect();
+            merge_channels(&channels, width, height, color)
+        }
+        2 => {
+            let buffer: Vec<u16> = buffer.iter().map(|v| (v * 257.0).round() as u16).collect();
+            let image = match channels.len() {
+                1 => {
+                    DynamicImage::ImageLuma16(ImageBuffer::from_raw(width, height, buffer).unwrap())
+                }
+                2 => DynamicImage::ImageLumaA16(
+                    ImageBuffer::from_raw(width, height, buffer).unwrap(),
+                ),
+                3 => {
+                    DynamicImage::ImageRgb16(ImageBuffer::from_raw(width, height, buffer).unwrap())
+                }
+                _ => {
+                    DynamicImage::ImageRgba16(ImageBuffer::from_raw(width, height, buffer).unwrap())
                 }
+            };
+            convert_color(image, color)
+        }
+        _ => {
+            let buffer: Vec<f32> = buffer.iter().map(|v| v / 255.0).collect();
+            let image = match channels.len() {
+                3 => {
+                    DynamicImage::ImageRgb32F(ImageBuffer::from_raw(width, height, buffer).unwrap())
+                }
+                _ => DynamicImage::ImageRgba32F(
+                    ImageBuffer::from_raw(width, height, buffer).unwrap(),
+                ),
+            };
+            convert_color(image, color)
+        }
+    }
+}
+
+// 逐通道浮点处理图像并保持原颜色类型，数值按8位范围表示
+// process_alpha 为 false 时透明通道原样保留
+pub fn map_channels_f32<F>(image: &DynamicImage, process_alpha: bool, f: F) -> DynamicImage
+where
+    F: Fn(&[f32]) -> Vec<f32>,
+{
+    let has_alpha = image.color().has_alpha();
+    let channel_count = image.color().channel_count() as usize;
+
+    let channels: Vec<Vec<f32>> = split_channels_f32(image)
+        .into_iter()
+        .enumerate()
+        .map(|(c, channel)| {
+            if has_alpha && c == channel_count - 1 && !process_alpha {
+                channel
+            } else {
+                f(&channel)
             }
+        })
+        .collect();
+
+    merge_channels_f32(&channels, image.width(), image.height(), image.color())
+}
+
+// 图像平滑
+pub fn smooth_image(
+    image: &DynamicImage,
+    radius: u32,
+    border: BorderMode,
+    process_alpha: bool,
+) -> DynamicImage {
+    let width = image.width() as usize;
+    let height = image.height() as usize;
+    let radius = radius as usize;
+
+    // 16位及浮点图像无法使用8位直方图，逐像素选取中值
+    if is_high_depth(image) {
+        return map_channels_f32(image, process_alpha, |channel| {
+            let channel = pad_channel(channel, width, height, radius, radius, border);
+            let channel =
+                median_select_channel(&channel, width + 2 * radius, height + 2 * radius, radius);
+            crop_channel(&channel, width, height, radius, radius)
+        });
+    }
 
-            let mut red_list = Vec::new();
-            let mut green_list = Vec::new();
-            let mut blue_list = Vec::new();
+    map_channels(image, process_alpha, |channel| {
+        let channel = pad_channel(channel, width, height, radius, radius, border);
+        let channel =
+            median_filter_channel(&channel, width + 2 * radius, height + 2 * radius, radius);
+        crop_channel(&channel, width, height, radius, radius)
+    })
+}
+
+// 单通道浮点中值滤波，逐个窗口部分排序选取中值
+// 距边缘不足 radius 的像素保持原值，边界需事先扩展
+fn median_select_channel(channel: &[f32], width: usize, height: usize, radius: usize) -> Vec<f32> {
+    let mut output = channel.to_vec();
+    let size = 2 * radius + 1;
+    if width < size || height < size {
+        return output;
+    }
 
-            for v in list.iter() {
-                red_list.push(v.0[0]);
-                green_list.push(v.0[1]);
-                blue_list.push(v.0[2]);
+    let half = size * size / 2;
+    let mut window = Vec::with_capacity(size * size);
+    for row in radius..height - radius {
+        for col in radius..width - radius {
+            window.clear();
+            for y in row - radius..=row + radius {
+                window.extend_from_slice(
+                    &channel[y * width + col - radius..=y * width + col + radius],
+                );
             }
 
-            let mut value: [i32; 3] = [0, 0, 0];
+            output[row * width + col] =
+                *window.select_nth_unstable_by(half, |a, b| a.total_cmp(b)).1;
+        }
+    }
+
+    output
+}
 
-            for (k, v) in laplace_filter.iter().enumerate() {
-                value[0] = value[0] + v * red_list[k] as i32;
-                value[1] = value[1] + v * green_list[k] as i32;
-                value[2] = value[2] + v * blue_list[k] as i32;
+// 单通道常数时间中值滤波（Perreault-Hébert 直方图算法）
+// 每列维护一个直方图，窗口直方图随列滑动增减，计算量与半径无关
+// 距边缘不足 radius 的像素保持原值，边界需事先扩展
+fn median_filter_channel(channel: &[u8], width: usize, height: usize, radius: usize) -> Vec<u8> {
+    let mut output = channel.to_vec();
+    let size = 2 * radius + 1;
+    if width < size || height < size {
+        return output;
+    }
+
+    // 中值在排序后序列中的下标
+    let half = (size * size / 2) as u32;
+
+    // 列直方图分为粗（16级）和细（256级）两层，便于快速查找中值
+    let mut column_fine = vec![[0_u16; 256]; width];
+    let mut column_coarse = vec![[0_u16; 16]; width];
+    for row in 0..size {
+        for col in 0..width {
+            let v = channel[row * width + col] as usize;
+            column_fine[col][v] += 1;
+            column_coarse[col][v >> 4] += 1;
+        }
+    }
+
+    for row in radius..height - radius {
+        // 列直方图下移一行
+        if row > radius {
+            for col in 0..width {
+                let old = channel[(row - radius - 1) * width + col] as usize;
+                let new = channel[(row + radius) * width + col] as usize;
+                column_fine[col][old] -= 1;
+                column_coarse[col][old >> 4] -= 1;
+                column_fine[col][new] += 1;
+                column_coarse[col][new >> 4] += 1;
             }
+        }
 
-            let pixel = buffer[i as usize][j as usize].channels();
-            for (k, v) in value.iter_mut().enumerate() {
-                *v = *v * index + pixel[k] as i32;
+        let mut kernel_fine = [0_u32; 256];
+        let mut kernel_coarse = [0_u32; 16];
+        for col in 0..size {
+            add_column_histogram(
+                &mut kernel_fine,
+                &mut kernel_coarse,
+                &column_fine[col],
+                &column_coarse[col],
+            );
+        }
+
+        for col in radius..width - radius {
+            // 窗口直方图右移一列
+            if col > radius {
+                remove_column_histogram(
+                    &mut kernel_fine,
+                    &mut kernel_coarse,
+                    &column_fine[col - radius - 1],
+                    &column_coarse[col - radius - 1],
+                );
+                add_column_histogram(
+                    &mut kernel_fine,
+                    &mut kernel_coarse,
+                    &column_fine[col + radius],
+                    &column_coarse[col + radius],
+                );
             }
 
-            let pixel = Rgba([
-                value[0].clamp(0, 255) as u8,
-                value[1].clamp(0, 255) as u8,
-                value[2].clamp(0, 255) as u8,
-                255,
-            ]);
+            output[row * width + col] = histogram_median(&kernel_fine, &kernel_coarse, half);
+        }
+    }
+
+    output
+}
+
+fn add_column_histogram(
+    kernel_fine: &mut [u32; 256],
+    kernel_coarse: &mut [u32; 16],
+    column_fine: &[u16; 256],
+    column_coarse: &[u16; 16],
+) {
+    for (k, v) in column_fine.iter().enumerate() {
+        kernel_fine[k] += *v as u32;
+    }
+    for (k, v) in column_coarse.iter().enumerate() {
+        kernel_coarse[k] += *v as u32;
+    }
+}
+
+fn remove_column_histogram(
+    kernel_fine: &mut [u32; 256],
+    kernel_coarse: &mut [u32; 16],
+    column_fine: &[u16; 256],
+    column_coarse: &[u16; 16],
+) {
+    for (k, v) in column_fine.iter().enumerate() {
+        kernel_fine[k] -= *v as u32;
+    }
+    for (k, v) in column_coarse.iter().enumerate() {
+        kernel_coarse[k] -= *v as u32;
+    }
+}
+
+// 先在粗直方图中定位中值所在区间，再在细直方图中查找
+fn histogram_median(fine: &[u32; 256], coarse: &[u32; 16], half: u32) -> u8 {
+    let mut count = 0;
+    let mut bucket = 0;
+    while count + coarse[bucket] <= half {
+        count += coarse[bucket];
+        bucket += 1;
+    }
+
+    let mut value = bucket * 16;
+    while count + fine[value] <= half {
+        count += fine[value];
+        value += 1;
+    }
+
+    value as u8
+}
 
-            new_buffer[i as usize][j as usize] = pixel;
+// 卷积核，宽高均须为奇数
+#[derive(Clone, Debug, PartialEq)]
+pub enum Kernel {
+    // 二维卷积核，按行存储
+    Full {
+        width: usize,
+        height: usize,
+        data: Vec<f32>,
+    },
+    // 可分离卷积核，先水平方向后垂直方向
+    Separable {
+        horizontal: Vec<f32>,
+        vertical: Vec<f32>,
+    },
+}
+
+impl Kernel {
+    pub fn new(width: usize, height: usize, data: Vec<f32>) -> Kernel {
+        assert!(width % 2 == 1 && height % 2 == 1, "卷积核尺寸必须为奇数");
+        assert_eq!(width * height, data.len(), "卷积核数据长度与尺寸不符");
+
+        Kernel::Full {
+            width,
+            height,
+            data,
         }
     }
 
-    let new_buffer: Vec<Rgba<u8>> = new_buffer.into_iter().flatten().collect();
-    let mut image = RgbaImage::new(width, height);
+    pub fn separable(horizontal: Vec<f32>, vertical: Vec<f32>) -> Kernel {
+        assert!(
+            horizontal.len() % 2 == 1 && vertical.len() % 2 == 1,
+            "卷积核尺寸必须为奇数"
+        );
 
-    for (k, v) in image.pixels_mut().enumerate() {
-        *v = new_buffer[k];
+        Kernel::Separable {
+            horizontal,
+            vertical,
+        }
     }
 
-    let image = image::DynamicImage::ImageRgba8(image);
-    image
+    // 卷积核宽高
+    pub fn size(&self) -> (usize, usize) {
+        match self {
+            Kernel::Full { width, height, .. } => (*width, *height),
+            Kernel::Separable {
+                horizontal,
+                vertical,
+            } => (horizontal.len(), vertical.len()),
+        }
+    }
+
+    // 按行展开的二维权值
+    pub fn weights(&self) -> Vec<f32> {
+        match self {
+            Kernel::Full { data, .. } => data.clone(),
+            Kernel::Separable {
+                horizontal,
+                vertical,
+            } => vertical
+                .iter()
+                .flat_map(|v| horizontal.iter().map(move |h| v * h))
+                .collect(),
+        }
+    }
+
+    // 权值之和
+    pub fn sum(&self) -> f32 {
+        match self {
+            Kernel::Full { data, .. } => data.iter().sum(),
+            Kernel::Separable {
+                horizontal,
+                vertical,
+            } => horizontal.iter().sum::<f32>() * vertical.iter().sum::<f32>(),
+        }
+    }
+
+    // 权值除以总和，总和为0时不变
+    pub fn normalized(&self) -> Kernel {
+        let sum = self.sum();
+        if sum.abs() < f32::EPSILON {
+            return self.clone();
+        }
+
+        match self {
+            Kernel::Full {
+                width,
+                height,
+                data,
+            } => Kernel::new(*width, *height, data.iter().map(|v| v / sum).collect()),
+            Kernel::Separable {
+                horizontal,
+                vertical,
+            } => {
+                let h_sum: f32 = horizontal.iter().sum();
+                let v_sum: f32 = vertical.iter().sum();
+                if h_sum.abs() < f32::EPSILON || v_sum.abs() < f32::EPSILON {
+                    return Kernel::new(
+                        horizontal.len(),
+                        vertical.len(),
+                        self.weights().iter().map(|v| v / sum).collect(),
+                    );
+                }
+
+                Kernel::separable(
+                    horizontal.iter().map(|v| v / h_sum).collect(),
+                    vertical.iter().map(|v| v / v_sum).collect(),
+                )
+            }
+        }
+    }
+
+    // 均值滤波
+    pub fn box_filter(size: usize) -> Kernel {
+        Kernel::separable(vec![1.0; size], vec![1.0; size])
+    }
+
+    // 高斯滤波，sigma 不大于0时按尺寸估计
+    pub fn gaussian(size: usize, sigma: f32) -> Kernel {
+        let sigma = if sigma > 0.0 {
+            sigma
+        } else {
+            0.3 * ((size as f32 - 1.0) * 0.5 - 1.0) + 0.8
+        };
+
+        let radius = (size / 2) as i32;
+        let weights: Vec<f32> = (-radius..=radius)
+            .map(|x| (-(x * x) as f32 / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let sum: f32 = weights.iter().sum();
+        let weights: Vec<f32> = weights.iter().map(|v| v / sum).collect();
+
+        Kernel::separable(weights.clone(), weights)
+    }
+
+    // 4邻域拉普拉斯算子
+    pub fn laplacian4() -> Kernel {
+        Kernel::new(3, 3, vec![0.0, 1.0, 0.0, 1.0, -4.0, 1.0, 0.0, 1.0, 0.0])
+    }
+
+    // 8邻域拉普拉斯算子
+    pub fn laplacian8() -> Kernel {
+        Kernel::new(3, 3, vec![1.0, 1.0, 1.0, 1.0, -8.0, 1.0, 1.0, 1.0, 1.0])
+    }
+
+    // 浮雕
+    pub fn emboss() -> Kernel {
+        Kernel::new(3, 3, vec![-2.0, -1.0, 0.0, -1.0, 1.0, 1.0, 0.0, 1.0, 2.0])
+    }
+
+    // Prewitt 水平梯度
+    pub fn prewitt_x() -> Kernel {
+        Kernel::separable(vec![-1.0, 0.0, 1.0], vec![1.0, 1.0, 1.0])
+    }
+
+    // Prewitt 垂直梯度
+    pub fn prewitt_y() -> Kernel {
+        Kernel::separable(vec![1.0, 1.0, 1.0], vec![-1.0, 0.0, 1.0])
+    }
+
+    // Sobel 水平梯度
+    pub fn sobel_x() -> Kernel {
+        Kernel::separable(vec![-1.0, 0.0, 1.0], vec![1.0, 2.0, 1.0])
+    }
+
+    // Sobel 垂直梯度
+    pub fn sobel_y() -> Kernel {
+        Kernel::separable(vec![1.0, 2.0, 1.0], vec![-1.0, 0.0, 1.0])
+    }
+}
+
+// 单通道浮点卷积，卷积核不翻转（与逐点相关运算相同）
+pub fn convolve_channel(
+    channel: &[f32],
+    width: usize,
+    height: usize,
+    kernel: &Kernel,
+    border: BorderMode,
+) -> Vec<f32> {
+    let (kernel_width, kernel_height) = kernel.size();
+    let pad_x = kernel_width / 2;
+    let pad_y = kernel_height / 2;
+
+    match kernel {
+        Kernel::Full { data, .. } => {
+            let padded = pad_channel(channel, width, height, pad_x, pad_y, border);
+            let padded_width = width + 2 * pad_x;
+
+            let mut buffer = vec![0.0_f32; width * height];
+            for i in 0..height {
+                for j in 0..width {
+                    let mut value = 0.0;
+                    for (k, v) in data.iter().enumerate() {
+                        let a = i + k / kernel_width;
+                        let b = j + k % kernel_width;
+                        value += v * padded[a * padded_width + b];
+                    }
+
+                    buffer[i * width + j] = value;
+                }
+            }
+
+            buffer
+        }
+        Kernel::Separable {
+            horizontal,
+            vertical,
+        } => {
+            // 先整体扩展边界，再依次做水平和垂直方向卷积
+            let padded = pad_channel(channel, width, height, pad_x, pad_y, border);
+            let padded_width = width + 2 * pad_x;
+            let padded_height = height + 2 * pad_y;
+
+            let mut temp = vec![0.0_f32; width * padded_height];
+            for i in 0..padded_height {
+                for j in 0..width {
+                    let row = &padded[i * padded_width + j..i * padded_width + j + kernel_width];
+                    temp[i * width + j] = row.iter().zip(horizontal).map(|(a, b)| a * b).sum();
+                }
+            }
+
+            let mut buffer = vec![0.0_f32; width * height];
+            for i in 0..height {
+                for j in 0..width {
+                    let mut value = 0.0;
+                    for (k, v) in vertical.iter().enumerate() {
+                        value += v * temp[(i + k) * width + j];
+                    }
+
+                    buffer[i * width + j] = value;
+                }
+            }
+
+            buffer
+        }
+    }
+}
+
+// 图像卷积，逐通道处理并保留透明通道
+pub fn convolve(
+    image: &DynamicImage,
+    kernel: &Kernel,
+    normalize: bool,
+    border: BorderMode,
+) -> DynamicImage {
+    let kernel = if normalize {
+        kernel.normalized()
+    } else {
+        kernel.clone()
+    };
+
+    convolve_image(image, &kernel, border, false)
+}
+
+// 逐通道卷积，结果截断到 0~255 后按原位深保存
+fn convolve_image(
+    image: &DynamicImage,
+    kernel: &Kernel,
+    border: BorderMode,
+    process_alpha: bool,
+) -> DynamicImage {
+    let width = image.width() as usize;
+    let height = image.height() as usize;
+
+    map_channels_f32(image, process_alpha, |channel| {
+        convolve_channel(channel, width, height, kernel, border)
+    })
+}
+
+// 图像锐化
+pub fn sharpen_image(
+    image: &DynamicImage,
+    index: i32,
+    border: BorderMode,
+    process_alpha: bool,
+) -> DynamicImage {
+    // 原图加上 index 倍拉普拉斯结果，合并为一个卷积核
+    let mut laplace_filter = Kernel::laplacian8().weights();
+    for v in laplace_filter.iter_mut() {
+        *v *= index as f32;
+    }
+    laplace_filter[4] += 1.0;
+
+    convolve_image(
+        image,
+        &Kernel::new(3, 3, laplace_filter),
+        border,
+        process_alpha,
+    )
+}
+
+// 将原图与高斯模糊结果逐像素组合，透明通道保持不变
+fn combine_with_blur<F>(image: &DynamicImage, sigma: f32, f: F) -> DynamicImage
+where
+    F: Fn(f32, f32) -> f32,
+{
+    let blurred = split_channels_f32(&blur_image(image, sigma));
+    let has_alpha = image.color().has_alpha();
+    let mut channels = split_channels_f32(image);
+    let channel_count = channels.len();
+
+    for (c, channel) in channels.iter_mut().enumerate() {
+        if has_alpha && c == channel_count - 1 {
+            continue;
+        }
+
+        for (v, b) in channel.iter_mut().zip(&blurred[c]) {
+            *v = f(*v, *b);
+        }
+    }
+
+    merge_channels_f32(&channels, image.width(), image.height(), image.color())
+}
+
+// 反锐化掩模锐化，原图与模糊图之差小于 threshold 的像素视为噪声不做增强
+pub fn unsharp_mask_image(
+    image: &DynamicImage,
+    sigma: f32,
+    amount: f32,
+    threshold: u8,
+) -> DynamicImage {
+    let threshold = threshold as f32;
+
+    combine_with_blur(image, sigma, |v, b| {
+        let mask = v - b;
+        if mask.abs() < threshold {
+            v
+        } else {
+            v + amount * mask
+        }
+    })
+}
+
+// 高提升滤波，结果为 boost 倍原图减去模糊图，boost 为1时即高通滤波
+pub fn high_boost_image(image: &DynamicImage, sigma: f32, boost: f32) -> DynamicImage {
+    combine_with_blur(image, sigma, |v, b| boost * v - b)
 }
 
 // 图像分割
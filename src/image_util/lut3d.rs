@@ -0,0 +1,327 @@
+use std::fs::{self, File};
+use std::io::Write;
+
+use image::{DynamicImage, ImageBuffer, Rgb};
+
+// 3D LUT 插值方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LutInterpolation {
+    Trilinear,
+    Tetrahedral,
+}
+
+// 3D 颜色查找表，data 按 .cube 约定排列：R 变化最快，其次 G，最后 B
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lut3d {
+    pub title: String,
+    pub size: usize,
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+    pub data: Vec<[f32; 3]>,
+}
+
+fn parse_floats(values: &[&str], line: usize) -> Result<[f32; 3], String> {
+    if values.len() != 3 {
+        return Err(format!("第{}行应为3个数值", line));
+    }
+
+    let mut result = [0.0; 3];
+    for (v, text) in result.iter_mut().zip(values.iter()) {
+        *v = text
+            .parse()
+            .map_err(|_| format!("第{}行数值格式错误：{}", line, text))?;
+    }
+
+    Ok(result)
+}
+
+impl Lut3d {
+    // 恒等查找表
+    pub fn identity(size: usize) -> Lut3d {
+        let size = size.max(2);
+        let step = (size - 1) as f32;
+        let mut data = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    data.push([r as f32 / step, g as f32 / step, b as f32 / step]);
+                }
+            }
+        }
+
+        Lut3d {
+            title: String::new(),
+            size,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            data,
+        }
+    }
+
+    // 解析 Adobe / Resolve 的 .cube 文本
+    pub fn parse_cube(text: &str) -> Result<Lut3d, String> {
+        let mut lut = Lut3d {
+            title: String::new(),
+            size: 0,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            data: Vec::new(),
+        };
+
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words[0] {
+                "TITLE" => {
+                    lut.title = line["TITLE".len()..].trim().trim_matches('"').to_string();
+                }
+                "LUT_3D_SIZE" => {
+                    lut.size = words
+                        .get(1)
+                        .and_then(|v| v.parse().ok())
+                        .filter(|v| *v >= 2)
+                        .ok_or(format!("第{}行 LUT_3D_SIZE 无效", number))?;
+                }
+                "LUT_1D_SIZE" => return Err("不支持 1D LUT".to_string()),
+                "DOMAIN_MIN" => lut.domain_min = parse_floats(&words[1..], number)?,
+                "DOMAIN_MAX" => lut.domain_max = parse_floats(&words[1..], number)?,
+                // 其他关键字忽略
+                word if word.starts_with(|c: char| c.is_ascii_alphabetic()) => {}
+                _ => lut.data.push(parse_floats(&words, number)?),
+            }
+        }
+
+        if lut.size == 0 {
+            return Err("缺少 LUT_3D_SIZE".to_string());
+        }
+        if lut.data.len() != lut.size.pow(3) {
+            return Err(format!(
+                "数据应为{}行，实际为{}行",
+                lut.size.pow(3),
+                lut.data.len()
+            ));
+        }
+
+        Ok(lut)
+    }
+
+    // 读取 .cube 文件
+    pub fn load_cube(path: &str) -> Result<Lut3d, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("无法读取文件：{}", e))?;
+        Lut3d::parse_cube(&text)
+    }
+
+    // 输出为 .cube 文本
+    pub fn to_cube(&self) -> String {
+        let mut text = String::new();
+        if !self.title.is_empty() {
+            text.push_str(&format!("TITLE \"{}\"\n", self.title));
+        }
+        text.push_str(&format!("LUT_3D_SIZE {}\n", self.size));
+        if self.domain_min != [0.0; 3] || self.domain_max != [1.0; 3] {
+            let [a, b, c] = self.domain_min;
+            text.push_str(&format!("DOMAIN_MIN {} {} {}\n", a, b, c));
+            let [a, b, c] = self.domain_max;
+            text.push_str(&format!("DOMAIN_MAX {} {} {}\n", a, b, c));
+        }
+
+        for [r, g, b] in &self.data {
+            text.push_str(&format!("{:.6} {:.6} {:.6}\n", r, g, b));
+        }
+
+        text
+    }
+
+    // 保存 .cube 文件
+    pub fn save_cube(&self, path: &str) -> Result<(), String> {
+        let mut f = File::create(path).map_err(|e| format!("无法创建文件：{}", e))?;
+        f.write_all(self.to_cube().as_bytes())
+            .map_err(|e| format!("无法写入文件：{}", e))
+    }
+
+    // 恒等格点图像，宽 size²、高 size，像素按 .cube 顺序排列
+    // 对其做逐像素的颜色调整后可用 from_lattice_image 得到对应的查找表
+    pub fn identity_image(size: usize) -> DynamicImage {
+        let size = size.max(2);
+        let identity = Lut3d::identity(size);
+        let mut image = ImageBuffer::new((size * size) as u32, size as u32);
+        for (pixel, color) in image.pixels_mut().zip(identity.data.iter()) {
+            *pixel = Rgb(color.map(|v| (v * 65535.0).round() as u16));
+        }
+
+        DynamicImage::ImageRgb16(image)
+    }
+
+    // 由按 .cube 顺序排列的格点图像生成查找表
+    pub fn from_lattice_image(image: &DynamicImage, size: usize) -> Result<Lut3d, String> {
+        let pixels = image.to_rgb32f();
+        if (pixels.width() * pixels.height()) as usize != size.pow(3) {
+            return Err(format!("图像像素数应为{}", size.pow(3)));
+        }
+
+        Ok(Lut3d {
+            title: String::new(),
+            size,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            data: pixels.pixels().map(|p| [p[0], p[1], p[2]]).collect(),
+        })
+    }
+
+    // 将逐像素的颜色调整作用于恒等格点图像，生成等效的查找表
+    // 调整须按满量程比例处理，不能依赖格点图像的位深
+    pub fn from_adjustments<F>(size: usize, adjust: F) -> Result<Lut3d, String>
+    where
+        F: Fn(&DynamicImage) -> DynamicImage,
+    {
+        let size = size.max(2);
+        Lut3d::from_lattice_image(&adjust(&Lut3d::identity_image(size)), size)
+    }
+
+    // 读取 HaldCLUT 图像，level 级的 Hald 图像为 level³×level³ 的正方形，对应边长 level² 的查找表
+    pub fn from_hald(image: &DynamicImage) -> Result<Lut3d, String> {
+        let (width, height) = (image.width(), image.height());
+        let level = (width as f64).cbrt().round() as u32;
+        if width != height || level < 2 || level.pow(3) != width {
+            return Err("不是有效的 HaldCLUT 图像".to_string());
+        }
+
+        Lut3d::from_lattice_image(image, (level * level) as usize)
+    }
+
+    fn at(&self, r: usize, g: usize, b: usize) -> [f32; 3] {
+        self.data[(b * self.size + g) * self.size + r]
+    }
+
+    // 查找颜色，输入与输出均为 0.0~1.0
+    pub fn lookup(&self, rgb: [f32; 3], interpolation: LutInterpolation) -> [f32; 3] {
+        let max = (self.size - 1) as f32;
+
+        // 输入映射到格点坐标
+        let mut base = [0_usize; 3];
+        let mut fraction = [0.0_f32; 3];
+        for c in 0..3 {
+            let range = self.domain_max[c] - self.domain_min[c];
+            let v = if range > 0.0 {
+                (rgb[c] - self.domain_min[c]) / range
+            } else {
+                0.0
+            };
+            let v = (v * max).clamp(0.0, max);
+            base[c] = (v.floor() as usize).min(self.size - 2);
+            fraction[c] = v - base[c] as f32;
+        }
+
+        let [r, g, b] = base;
+        let corner = |dr: usize, dg: usize, db: usize| self.at(r + dr, g + dg, b + db);
+        let mix = |weights: &[(f32, [f32; 3])]| {
+            let mut result = [0.0; 3];
+            for (w, c) in weights {
+                for k in 0..3 {
+                    result[k] += w * c[k];
+                }
+            }
+            result
+        };
+        let [fr, fg, fb] = fraction;
+
+        match interpolation {
+            LutInterpolation::Trilinear => mix(&[
+                ((1.0 - fr) * (1.0 - fg) * (1.0 - fb), corner(0, 0, 0)),
+                (fr * (1.0 - fg) * (1.0 - fb), corner(1, 0, 0)),
+                ((1.0 - fr) * fg * (1.0 - fb), corner(0, 1, 0)),
+                (fr * fg * (1.0 - fb), corner(1, 1, 0)),
+                ((1.0 - fr) * (1.0 - fg) * fb, corner(0, 0, 1)),
+                (fr * (1.0 - fg) * fb, corner(1, 0, 1)),
+                ((1.0 - fr) * fg * fb, corner(0, 1, 1)),
+                (fr * fg * fb, corner(1, 1, 1)),
+            ]),
+            // 按分量大小将格点立方体分为6个四面体
+            LutInterpolation::Tetrahedral => {
+                let (c000, c111) = (corner(0, 0, 0), corner(1, 1, 1));
+                if fr > fg {
+                    if fg > fb {
+                        mix(&[
+                            (1.0 - fr, c000),
+                            (fr - fg, corner(1, 0, 0)),
+                            (fg - fb, corner(1, 1, 0)),
+                            (fb, c111),
+                        ])
+                    } else if fr > fb {
+                        mix(&[
+                            (1.0 - fr, c000),
+                            (fr - fb, corner(1, 0, 0)),
+                            (fb - fg, corner(1, 0, 1)),
+                            (fg, c111),
+                        ])
+                    } else {
+                        mix(&[
+                            (1.0 - fb, c000),
+                            (fb - fr, corner(0, 0, 1)),
+                            (fr - fg, corner(1, 0, 1)),
+                            (fg, c111),
+                        ])
+                    }
+                } else if fb > fg {
+                    mix(&[
+                        (1.0 - fb, c000),
+                        (fb - fg, corner(0, 0, 1)),
+                        (fg - fr, corner(0, 1, 1)),
+                        (fr, c111),
+                    ])
+                } else if fb > fr {
+                    mix(&[
+                        (1.0 - fg, c000),
+                        (fg - fb, corner(0, 1, 0)),
+                        (fb - fr, corner(0, 1, 1)),
+                        (fr, c111),
+                    ])
+                } else {
+                    mix(&[
+                        (1.0 - fg, c000),
+                        (fg - fr, corner(0, 1, 0)),
+                        (fr - fb, corner(1, 1, 0)),
+                        (fb, c111),
+                    ])
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, Rgb, RgbImage};
+
+    use super::super::image_util::{
+        adjust_brightness_image, adjust_brightness_normalized, lut3d_image,
+    };
+    use super::*;
+
+    // 导出的查找表与8位图像上的亮度预览一致
+    #[test]
+    fn exported_brightness_matches_preview() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(16, 16, |x, y| {
+            Rgb([(x * 16) as u8, (y * 16) as u8, ((x + y) * 8) as u8])
+        }));
+        let brightness = 40;
+
+        let lut = Lut3d::from_adjustments(33, |image| {
+            adjust_brightness_normalized(image, brightness as f32 / 255.0)
+        })
+        .unwrap();
+        let exported = lut3d_image(&image, &lut, LutInterpolation::Tetrahedral).to_rgb8();
+        let preview = adjust_brightness_image(&image, brightness).to_rgb8();
+
+        for (a, b) in exported.pixels().zip(preview.pixels()) {
+            for c in 0..3 {
+                assert!((a[c] as i32 - b[c] as i32).abs() <= 1, "{:?} {:?}", a, b);
+            }
+        }
+    }
+}
@@ -1 +1,13 @@
-pub mod image_util;
\ No newline at end of file
+pub mod image_util;
+pub mod edge;
+pub mod threshold;
+pub mod morphology;
+pub mod components;
+pub mod contour;
+pub mod watershed;
+pub mod cluster;
+pub mod region;
+pub mod color;
+pub mod transform;
+pub mod lut3d;
+pub mod colormap;
\ No newline at end of file
@@ -0,0 +1,216 @@
+use image::{DynamicImage, GrayImage};
+
+use super::image_util::{
+    crop_channel, image_to_gray, local_extreme_channel, pad_channel, BorderMode,
+};
+
+// 结构元素，以中心为原点，宽高均须为奇数
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructuringElement {
+    width: usize,
+    height: usize,
+    data: Vec<bool>,
+}
+
+impl StructuringElement {
+    // 自定义结构元素，按行存储
+    pub fn custom(width: usize, height: usize, data: Vec<bool>) -> StructuringElement {
+        assert!(width % 2 == 1 && height % 2 == 1, "结构元素尺寸必须为奇数");
+        assert_eq!(width * height, data.len(), "结构元素数据长度与尺寸不符");
+
+        StructuringElement {
+            width,
+            height,
+            data,
+        }
+    }
+
+    // 正方形
+    pub fn square(size: usize) -> StructuringElement {
+        let size = size | 1;
+        StructuringElement::custom(size, size, vec![true; size * size])
+    }
+
+    // 十字形
+    pub fn cross(size: usize) -> StructuringElement {
+        let size = size | 1;
+        let center = size / 2;
+        let data = (0..size * size)
+            .map(|k| k / size == center || k % size == center)
+            .collect();
+
+        StructuringElement::custom(size, size, data)
+    }
+
+    // 圆盘
+    pub fn disk(radius: usize) -> StructuringElement {
+        let size = 2 * radius + 1;
+        let r = radius as i64;
+        let data = (0..size * size)
+            .map(|k| {
+                let y = (k / size) as i64 - r;
+                let x = (k % size) as i64 - r;
+                x * x + y * y <= r * r + r
+            })
+            .collect();
+
+        StructuringElement::custom(size, size, data)
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    // 全部为真时可使用可分离的快速算法
+    fn is_rectangle(&self) -> bool {
+        self.data.iter().all(|v| *v)
+    }
+
+    // 相对原点的偏移量
+    fn offsets(&self) -> Vec<(usize, usize)> {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| **v)
+            .map(|(k, _)| (k % self.width, k / self.width))
+            .collect()
+    }
+
+    // 关于原点的反射
+    fn reflected(&self) -> StructuringElement {
+        let mut data = self.data.clone();
+        data.reverse();
+
+        StructuringElement::custom(self.width, self.height, data)
+    }
+}
+
+// 形态学运算
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MorphologyOperation {
+    // 腐蚀
+    Erode,
+    // 膨胀
+    Dilate,
+    // 开运算
+    Open,
+    // 闭运算
+    Close,
+    // 形态学梯度
+    Gradient,
+    // 顶帽
+    TopHat,
+    // 黑帽
+    BlackHat,
+}
+
+// 在结构元素覆盖范围内取最大值或最小值
+fn extreme_channel(
+    channel: &[u8],
+    width: usize,
+    height: usize,
+    element: &StructuringElement,
+    border: BorderMode,
+    max: bool,
+) -> Vec<u8> {
+    let (element_width, element_height) = element.size();
+    let pad_x = element_width / 2;
+    let pad_y = element_height / 2;
+
+    let padded = pad_channel(channel, width, height, pad_x, pad_y, border);
+    let padded_width = width + 2 * pad_x;
+    let padded_height = height + 2 * pad_y;
+
+    if element.is_rectangle() {
+        let buffer = local_extreme_channel(&padded, padded_width, padded_height, pad_x, pad_y, max);
+        return crop_channel(&buffer, width, height, pad_x, pad_y);
+    }
+
+    let offsets = element.offsets();
+    let mut buffer = vec![0_u8; width * height];
+    for i in 0..height {
+        for j in 0..width {
+            let values = offsets
+                .iter()
+                .map(|(x, y)| padded[(i + y) * padded_width + j + x]);
+            buffer[i * width + j] = if max {
+                values.max().unwrap_or(0)
+            } else {
+                values.min().unwrap_or(255)
+            };
+        }
+    }
+
+    buffer
+}
+
+// 灰度腐蚀，二值图像即为二值腐蚀
+pub fn erode_channel(
+    channel: &[u8],
+    width: usize,
+    height: usize,
+    element: &StructuringElement,
+    border: BorderMode,
+) -> Vec<u8> {
+    extreme_channel(channel, width, height, element, border, false)
+}
+
+// 灰度膨胀，使用反射后的结构元素
+pub fn dilate_channel(
+    channel: &[u8],
+    width: usize,
+    height: usize,
+    element: &StructuringElement,
+    border: BorderMode,
+) -> Vec<u8> {
+    extreme_channel(channel, width, height, &element.reflected(), border, true)
+}
+
+// 单通道形态学运算
+pub fn morphology_channel(
+    channel: &[u8],
+    width: usize,
+    height: usize,
+    operation: MorphologyOperation,
+    element: &StructuringElement,
+    border: BorderMode,
+) -> Vec<u8> {
+    let erode = |data: &[u8]| erode_channel(data, width, height, element, border);
+    let dilate = |data: &[u8]| dilate_channel(data, width, height, element, border);
+    let subtract = |a: &[u8], b: &[u8]| -> Vec<u8> {
+        a.iter().zip(b).map(|(a, b)| a.saturating_sub(*b)).collect()
+    };
+
+    match operation {
+        MorphologyOperation::Erode => erode(channel),
+        MorphologyOperation::Dilate => dilate(channel),
+        MorphologyOperation::Open => dilate(&erode(channel)),
+        MorphologyOperation::Close => erode(&dilate(channel)),
+        MorphologyOperation::Gradient => subtract(&dilate(channel), &erode(channel)),
+        MorphologyOperation::TopHat => subtract(channel, &dilate(&erode(channel))),
+        MorphologyOperation::BlackHat => subtract(&erode(&dilate(channel)), channel),
+    }
+}
+
+// 图像形态学运算，彩色图像先转为灰度
+pub fn morphology(
+    image: &DynamicImage,
+    operation: MorphologyOperation,
+    element: &StructuringElement,
+    border: BorderMode,
+) -> GrayImage {
+    let width = image.width();
+    let height = image.height();
+
+    let gray = image_to_gray(image);
+    let buffer = morphology_channel(
+        &gray,
+        width as usize,
+        height as usize,
+        operation,
+        element,
+        border,
+    );
+
+    GrayImage::from_vec(width, height, buffer).unwrap()
+}
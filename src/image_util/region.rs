@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+
+use image::{DynamicImage, GrayImage, Luma, Rgba, RgbaImage};
+
+use super::components::Connectivity;
+use super::image_util::split_channels;
+
+// 选区合并方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SelectionMode {
+    Replace,
+    Add,
+    Subtract,
+}
+
+// 区域生长：从种子点出发，合并与种子颜色差不超过 tolerance 的连通像素，返回掩模（255 为区域）
+// 彩色图像取各通道差值的最大值，透明通道不参与比较
+pub fn region_grow(
+    image: &DynamicImage,
+    seed: (u32, u32),
+    tolerance: f32,
+    connectivity: Connectivity,
+) -> GrayImage {
+    let (width, height) = (image.width(), image.height());
+    let mut mask = GrayImage::new(width, height);
+    if seed.0 >= width || seed.1 >= height {
+        return mask;
+    }
+
+    let mut channels = split_channels(image);
+    if image.color().has_alpha() {
+        channels.pop();
+    }
+
+    let (w, h) = (width as usize, height as usize);
+    let start = seed.1 as usize * w + seed.0 as usize;
+    let seed_color: Vec<f32> = channels.iter().map(|c| c[start] as f32).collect();
+    let similar = |index: usize| {
+        channels
+            .iter()
+            .zip(seed_color.iter())
+            .all(|(c, s)| (c[index] as f32 - s).abs() <= tolerance)
+    };
+
+    let mask_data: &mut [u8] = &mut mask;
+    let mut queue = VecDeque::from([start]);
+    mask_data[start] = 255;
+
+    while let Some(index) = queue.pop_front() {
+        let (x, y) = ((index % w) as i64, (index / w) as i64);
+        for (dx, dy) in connectivity.offsets() {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || ny < 0 || nx >= w as i64 || ny >= h as i64 {
+                continue;
+            }
+
+            let neighbor = ny as usize * w + nx as usize;
+            if mask_data[neighbor] == 0 && similar(neighbor) {
+                mask_data[neighbor] = 255;
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    mask
+}
+
+// 按合并方式将新区域并入选区，选区为空或尺寸不同时视为替换
+pub fn combine_selection(
+    selection: &GrayImage,
+    region: &GrayImage,
+    mode: SelectionMode,
+) -> GrayImage {
+    if mode == SelectionMode::Replace || selection.dimensions() != region.dimensions() {
+        return region.clone();
+    }
+
+    let mut result = selection.clone();
+    for (pixel, r) in result.pixels_mut().zip(region.pixels()) {
+        if r[0] > 0 {
+            *pixel = match mode {
+                SelectionMode::Add => Luma([255]),
+                _ => Luma([0]),
+            };
+        }
+    }
+
+    result
+}
+
+// 魔棒选区：从点击位置生长区域，并按合并方式更新已有选区
+pub fn magic_wand(
+    image: &DynamicImage,
+    selection: &GrayImage,
+    seed: (u32, u32),
+    tolerance: f32,
+    connectivity: Connectivity,
+    mode: SelectionMode,
+) -> GrayImage {
+    let region = region_grow(image, seed, tolerance, connectivity);
+    combine_selection(selection, &region, mode)
+}
+
+// 在图像上显示选区：选区内叠加半透明颜色，边缘用不透明颜色描出
+pub fn draw_selection(image: &DynamicImage, selection: &GrayImage, color: Rgba<u8>) -> RgbaImage {
+    let mut image = image.to_rgba8();
+    if selection.dimensions() != image.dimensions() {
+        return image;
+    }
+
+    let (width, height) = selection.dimensions();
+    let selected = |x: i64, y: i64| {
+        x >= 0
+            && y >= 0
+            && x < width as i64
+            && y < height as i64
+            && selection.get_pixel(x as u32, y as u32)[0] > 0
+    };
+
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let (x, y) = (x as i64, y as i64);
+        if !selected(x, y) {
+            continue;
+        }
+
+        let edge =
+            !(selected(x - 1, y) && selected(x + 1, y) && selected(x, y - 1) && selected(x, y + 1));
+        if edge {
+            *pixel = color;
+        } else {
+            for c in 0..3 {
+                pixel[c] = ((pixel[c] as u16 + color[c] as u16) / 2) as u8;
+            }
+        }
+    }
+
+    image
+}
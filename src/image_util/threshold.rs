@@ -0,0 +1,518 @@
+use image::{DynamicImage, GrayImage};
+
+use super::image_util::{gray_histogram, image_to_gray, local_extreme_channel, segmentate_image};
+
+// 全局阈值自动选取方法
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThresholdMethod {
+    // 最大类间方差
+    Otsu,
+    // 三角法
+    Triangle,
+    // Kapur 最大熵
+    Kapur,
+    // 迭代均值（isodata）
+    IsoData,
+    // Huang 模糊熵
+    Huang,
+}
+
+// 根据灰度直方图计算阈值，灰度大于阈值的像素为前景
+pub fn threshold_value(histogram: &[u32; 256], method: ThresholdMethod) -> u8 {
+    match method {
+        ThresholdMethod::Otsu => otsu(histogram),
+        ThresholdMethod::Triangle => triangle(histogram),
+        ThresholdMethod::Kapur => kapur(histogram),
+        ThresholdMethod::IsoData => isodata(histogram),
+        ThresholdMethod::Huang => huang(histogram),
+    }
+}
+
+// 自动阈值分割，返回阈值与二值图像
+pub fn auto_threshold(image: &DynamicImage, method: ThresholdMethod) -> (u8, GrayImage) {
+    let histogram = gray_histogram(&image_to_gray(image));
+    let threshold = threshold_value(&histogram, method);
+
+    (threshold, segmentate_image(image, threshold))
+}
+
+// 非零灰度级的最小值和最大值
+fn histogram_range(histogram: &[u32; 256]) -> Option<(usize, usize)> {
+    let first = histogram.iter().position(|v| *v > 0)?;
+    let last = histogram.iter().rposition(|v| *v > 0)?;
+
+    Some((first, last))
+}
+
+fn otsu(histogram: &[u32; 256]) -> u8 {
+    let total: f64 = histogram.iter().map(|v| *v as f64).sum();
+    let sum: f64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(k, v)| k as f64 * *v as f64)
+        .sum();
+
+    let mut weight = 0.0;
+    let mut weight_sum = 0.0;
+    let mut max_variance = -1.0;
+    let mut threshold = 0;
+
+    for (k, v) in histogram.iter().enumerate() {
+        weight += *v as f64;
+        weight_sum += k as f64 * *v as f64;
+        if weight == 0.0 || weight == total {
+            continue;
+        }
+
+        let mean0 = weight_sum / weight;
+        let mean1 = (sum - weight_sum) / (total - weight);
+        let variance = weight * (total - weight) * (mean0 - mean1) * (mean0 - mean1);
+        if variance > max_variance {
+            max_variance = variance;
+            threshold = k;
+        }
+    }
+
+    threshold as u8
+}
+
+fn triangle(histogram: &[u32; 256]) -> u8 {
+    let (first, last) = match histogram_range(histogram) {
+        Some(range) => range,
+        None => return 0,
+    };
+
+    let peak = (0..256).fold(0, |peak, k| {
+        if histogram[k] > histogram[peak] {
+            k
+        } else {
+            peak
+        }
+    });
+
+    // 在峰值距离较远的一侧连线
+    let (end, left) = if peak - first >= last - peak {
+        (first, true)
+    } else {
+        (last, false)
+    };
+    if end == peak {
+        return peak as u8;
+    }
+
+    let (x0, y0) = (peak as f64, histogram[peak] as f64);
+    let (x1, y1) = (end as f64, histogram[end] as f64);
+    let length = ((x1 - x0) * (x1 - x0) + (y1 - y0) * (y1 - y0)).sqrt();
+
+    let range: Vec<usize> = if left {
+        (end..=peak).collect()
+    } else {
+        (peak..=end).collect()
+    };
+
+    let mut max_distance = -1.0;
+    let mut threshold = peak;
+    for k in range {
+        let (x, y) = (k as f64, histogram[k] as f64);
+        let distance = ((y1 - y0) * x - (x1 - x0) * y + x1 * y0 - y1 * x0).abs() / length;
+        if distance > max_distance {
+            max_distance = distance;
+            threshold = k;
+        }
+    }
+
+    // 前景在峰值左侧时，阈值以下为前景，向下取一级使分割边界落在谷底
+    if left {
+        threshold.saturating_sub(1) as u8
+    } else {
+        threshold as u8
+    }
+}
+
+fn kapur(histogram: &[u32; 256]) -> u8 {
+    let total: f64 = histogram.iter().map(|v| *v as f64).sum();
+    if total == 0.0 {
+        return 0;
+    }
+
+    let p: Vec<f64> = histogram.iter().map(|v| *v as f64 / total).collect();
+    let entropy = |range: std::ops::RangeInclusive<usize>, weight: f64| -> f64 {
+        range
+            .filter(|k| p[*k] > 0.0)
+            .map(|k| {
+                let q = p[k] / weight;
+                -q * q.ln()
+            })
+            .sum()
+    };
+
+    let mut weight = 0.0;
+    let mut max_entropy = f64::MIN;
+    let mut threshold = 0;
+    for (k, v) in p.iter().enumerate().take(255) {
+        weight += v;
+        if weight <= 0.0 || weight >= 1.0 {
+            continue;
+        }
+
+        let value = entropy(0..=k, weight) + entropy(k + 1..=255, 1.0 - weight);
+        if value > max_entropy {
+            max_entropy = value;
+            threshold = k;
+        }
+    }
+
+    threshold as u8
+}
+
+fn isodata(histogram: &[u32; 256]) -> u8 {
+    let mean = |range: std::ops::RangeInclusive<usize>| -> Option<f64> {
+        let count: f64 = range.clone().map(|k| histogram[k] as f64).sum();
+        let sum: f64 = range.map(|k| k as f64 * histogram[k] as f64).sum();
+        if count > 0.0 {
+            Some(sum / count)
+        } else {
+            None
+        }
+    };
+
+    let mut threshold = match mean(0..=255) {
+        Some(v) => v,
+        None => return 0,
+    };
+
+    // 阈值取两类均值的中点，直至收敛
+    for _ in 0..256 {
+        let t = threshold.floor() as usize;
+        let (mean0, mean1) = match (mean(0..=t), mean(t + 1..=255)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => break,
+        };
+
+        let next = (mean0 + mean1) / 2.0;
+        if (next - threshold).abs() < 0.5 {
+            threshold = next;
+            break;
+        }
+        threshold = next;
+    }
+
+    threshold.floor() as u8
+}
+
+fn huang(histogram: &[u32; 256]) -> u8 {
+    let (first, last) = match histogram_range(histogram) {
+        Some(range) => range,
+        None => return 0,
+    };
+    if first == last {
+        return first as u8;
+    }
+
+    let term = 1.0 / (last - first) as f64;
+
+    // 前景与背景的累计均值
+    let mut mu_0 = [0.0_f64; 256];
+    let mut count = 0.0;
+    let mut sum = 0.0;
+    for (k, v) in histogram.iter().enumerate().take(last + 1).skip(first) {
+        count += *v as f64;
+        sum += k as f64 * *v as f64;
+        mu_0[k] = sum / count;
+    }
+
+    let mut mu_1 = [0.0_f64; 256];
+    let mut count = 0.0;
+    let mut sum = 0.0;
+    for (k, v) in histogram
+        .iter()
+        .enumerate()
+        .take(last + 1)
+        .skip(first + 1)
+        .rev()
+    {
+        count += *v as f64;
+        sum += k as f64 * *v as f64;
+        mu_1[k - 1] = sum / count;
+    }
+
+    let fuzzy_entropy = |mu: f64| -> f64 {
+        if mu <= 1e-6 || mu >= 1.0 - 1e-6 {
+            0.0
+        } else {
+            -mu * mu.ln() - (1.0 - mu) * (1.0 - mu).ln()
+        }
+    };
+
+    let mut min_entropy = f64::MAX;
+    let mut threshold = first;
+    for t in first..last {
+        let entropy: f64 = (first..=last)
+            .map(|k| {
+                let mean = if k <= t { mu_0[t] } else { mu_1[t] };
+                let mu = 1.0 / (1.0 + term * (k as f64 - mean).abs());
+                histogram[k] as f64 * fuzzy_entropy(mu)
+            })
+            .sum();
+
+        if entropy < min_entropy {
+            min_entropy = entropy;
+            threshold = t;
+        }
+    }
+
+    threshold as u8
+}
+
+// 多阈值 Otsu，将灰度分为 classes 类，返回 classes-1 个阈值
+pub fn multi_otsu_thresholds(histogram: &[u32; 256], classes: usize) -> Vec<u8> {
+    let classes = classes.clamp(2, 8);
+
+    // 前缀和，用于快速计算任意区间的像素数与灰度和
+    let mut count = [0.0_f64; 257];
+    let mut sum = [0.0_f64; 257];
+    for k in 0..256 {
+        count[k + 1] = count[k] + histogram[k] as f64;
+        sum[k + 1] = sum[k] + k as f64 * histogram[k] as f64;
+    }
+
+    // 区间 [a, b] 对类间方差的贡献
+    let cost = |a: usize, b: usize| -> f64 {
+        let n = count[b + 1] - count[a];
+        if n > 0.0 {
+            let s = sum[b + 1] - sum[a];
+            s * s / n
+        } else {
+            0.0
+        }
+    };
+
+    // 动态规划：best[m][t] 为将 [0, t] 分为 m+1 类的最大值
+    let mut best = vec![vec![f64::MIN; 256]; classes];
+    let mut split = vec![vec![0_usize; 256]; classes];
+    for (t, v) in best[0].iter_mut().enumerate() {
+        *v = cost(0, t);
+    }
+    for m in 1..classes {
+        for t in m..256 {
+            for s in m - 1..t {
+                let value = best[m - 1][s] + cost(s + 1, t);
+                if value > best[m][t] {
+                    best[m][t] = value;
+                    split[m][t] = s;
+                }
+            }
+        }
+    }
+
+    let mut thresholds = Vec::with_capacity(classes - 1);
+    let mut t = 255;
+    for m in (1..classes).rev() {
+        t = split[m][t];
+        thresholds.push(t as u8);
+    }
+    thresholds.reverse();
+
+    thresholds
+}
+
+// 多阈值 Otsu 分割，各类按等间隔灰度输出
+pub fn multi_otsu(image: &DynamicImage, classes: usize) -> (Vec<u8>, GrayImage) {
+    let width = image.width();
+    let height = image.height();
+
+    let gray = image_to_gray(image);
+    let thresholds = multi_otsu_thresholds(&gray_histogram(&gray), classes);
+    let step = 255.0 / thresholds.len() as f32;
+
+    let buffer: Vec<u8> = gray
+        .iter()
+        .map(|v| {
+            let class = thresholds.iter().filter(|t| v > t).count();
+            (class as f32 * step).round() as u8
+        })
+        .collect();
+
+    (
+        thresholds,
+        GrayImage::from_vec(width, height, buffer).unwrap(),
+    )
+}
+
+// 局部（自适应）阈值方法
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LocalThresholdMethod {
+    // 窗口均值减去 k
+    Mean,
+    // 高斯加权均值减去 k
+    Gaussian,
+    // 均值加 k 倍标准差
+    Niblack,
+    // 均值乘以 1 + k * (标准差 / 128 - 1)
+    Sauvola,
+    // 窗口最大最小值的中点，对比度低于 k 时按全局中值128判断
+    Bernsen,
+}
+
+impl LocalThresholdMethod {
+    // 常用的 k 参数
+    pub fn default_k(&self) -> f32 {
+        match self {
+            LocalThresholdMethod::Mean => 5.0,
+            LocalThresholdMethod::Gaussian => 5.0,
+            LocalThresholdMethod::Niblack => -0.2,
+            LocalThresholdMethod::Sauvola => 0.34,
+            LocalThresholdMethod::Bernsen => 15.0,
+        }
+    }
+}
+
+// 积分图，可在常数时间内求任意矩形窗口的均值与方差
+pub struct IntegralImage {
+    width: usize,
+    height: usize,
+    sum: Vec<f64>,
+    square_sum: Vec<f64>,
+}
+
+impl IntegralImage {
+    pub fn new<T: Copy + Into<f64>>(gray: &[T], width: usize, height: usize) -> IntegralImage {
+        let stride = width + 1;
+        let mut sum = vec![0.0_f64; stride * (height + 1)];
+        let mut square_sum = vec![0.0_f64; stride * (height + 1)];
+
+        for row in 0..height {
+            let mut row_sum = 0.0;
+            let mut row_square_sum = 0.0;
+            for col in 0..width {
+                let v: f64 = gray[row * width + col].into();
+                row_sum += v;
+                row_square_sum += v * v;
+
+                let k = (row + 1) * stride + col + 1;
+                sum[k] = sum[k - stride] + row_sum;
+                square_sum[k] = square_sum[k - stride] + row_square_sum;
+            }
+        }
+
+        IntegralImage {
+            width,
+            height,
+            sum,
+            square_sum,
+        }
+    }
+
+    // 窗口在边界处截断后的范围与像素数
+    fn window(&self, x: usize, y: usize, radius: usize) -> ([usize; 4], f64) {
+        let x0 = x.saturating_sub(radius);
+        let y0 = y.saturating_sub(radius);
+        let x1 = (x + radius + 1).min(self.width);
+        let y1 = (y + radius + 1).min(self.height);
+
+        ([x0, y0, x1, y1], ((x1 - x0) * (y1 - y0)) as f64)
+    }
+
+    fn area(&self, table: &[f64], [x0, y0, x1, y1]: [usize; 4]) -> f64 {
+        let stride = self.width + 1;
+        table[y1 * stride + x1] - table[y0 * stride + x1] - table[y1 * stride + x0]
+            + table[y0 * stride + x0]
+    }
+
+    // 以 (x, y) 为中心、半径为 radius 的窗口均值，窗口在边界处截断
+    pub fn window_mean(&self, x: usize, y: usize, radius: usize) -> f64 {
+        let (window, n) = self.window(x, y, radius);
+        self.area(&self.sum, window) / n
+    }
+
+    // 以 (x, y) 为中心、半径为 radius 的窗口均值与标准差，窗口在边界处截断
+    pub fn window_stats(&self, x: usize, y: usize, radius: usize) -> (f64, f64) {
+        let (window, n) = self.window(x, y, radius);
+        let mean = self.area(&self.sum, window) / n;
+        let variance = (self.area(&self.square_sum, window) / n - mean * mean).max(0.0);
+
+        (mean, variance.sqrt())
+    }
+}
+
+// 以积分图做3次盒式均值滤波近似高斯加权均值，计算量与窗口大小无关
+// sigma 与同尺寸的高斯卷积核相同，n 次宽度为 w 的盒式滤波方差为 n(w²-1)/12
+fn gaussian_mean(gray: &[u8], width: usize, height: usize, radius: usize) -> Vec<f64> {
+    let passes = 3.0;
+    let sigma = 0.3 * (radius as f64 - 1.0) + 0.8;
+    let box_width = (12.0 * sigma * sigma / passes + 1.0).sqrt();
+    let box_radius = ((box_width - 1.0) / 2.0).round() as usize;
+
+    let mut values: Vec<f64> = gray.iter().map(|v| *v as f64).collect();
+    for _ in 0..passes as usize {
+        let integral = IntegralImage::new(&values, width, height);
+        values = (0..width * height)
+            .map(|i| integral.window_mean(i % width, i / width, box_radius))
+            .collect();
+    }
+
+    values
+}
+
+// 局部阈值分割，window 为窗口边长，灰度大于局部阈值的像素为前景
+pub fn adaptive_threshold(
+    image: &DynamicImage,
+    method: LocalThresholdMethod,
+    window: u32,
+    k: f32,
+) -> GrayImage {
+    let width = image.width() as usize;
+    let height = image.height() as usize;
+    let radius = (window / 2).max(1) as usize;
+    let k = k as f64;
+
+    let gray = image_to_gray(image);
+    let binary = |v: u8, threshold: f64| if v as f64 > threshold { 255 } else { 0 };
+
+    let buffer: Vec<u8> = match method {
+        LocalThresholdMethod::Gaussian => {
+            let mean = gaussian_mean(&gray, width, height, radius);
+
+            gray.iter()
+                .zip(&mean)
+                .map(|(v, m)| binary(*v, m - k))
+                .collect()
+        }
+        LocalThresholdMethod::Bernsen => {
+            let max = local_extreme_channel(&gray, width, height, radius, radius, true);
+            let min = local_extreme_channel(&gray, width, height, radius, radius, false);
+
+            gray.iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    let contrast = max[i] as f64 - min[i] as f64;
+                    let middle = (max[i] as f64 + min[i] as f64) / 2.0;
+                    if contrast < k {
+                        binary(middle as u8, 128.0)
+                    } else {
+                        binary(*v, middle)
+                    }
+                })
+                .collect()
+        }
+        _ => {
+            let integral = IntegralImage::new(&gray, width, height);
+
+            gray.iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    let (mean, std) = integral.window_stats(i % width, i / width, radius);
+                    let threshold = match method {
+                        LocalThresholdMethod::Niblack => mean + k * std,
+                        LocalThresholdMethod::Sauvola => mean * (1.0 + k * (std / 128.0 - 1.0)),
+                        _ => mean - k,
+                    };
+
+                    binary(*v, threshold)
+                })
+                .collect()
+        }
+    };
+
+    GrayImage::from_vec(width as u32, height as u32, buffer).unwrap()
+}
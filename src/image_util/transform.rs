@@ -0,0 +1,154 @@
+use image::DynamicImage;
+
+use super::image_util::{is_high_depth, map_channels, map_color_channels, EqualizeMode};
+
+// 灰度变换，输入输出均归一化到 0.0~1.0
+#[derive(Clone, Debug, PartialEq)]
+pub enum IntensityTransform {
+    // 幂律变换 s = r^gamma
+    Gamma(f32),
+    // 对数变换 s = log(1 + c·r) / log(1 + c)
+    Log(f32),
+    // 反对数变换，对数变换的逆
+    InverseLog(f32),
+    // 将 [low, high] 线性拉伸到 [0, 1]
+    ContrastStretch { low: f32, high: f32 },
+    // 按百分位数裁剪后拉伸，low、high 为 0~100 的百分比，由各通道直方图确定拉伸范围
+    PercentileStretch { low: f32, high: f32 },
+    // 位平面分层，取第 n 位
+    BitPlane(u32),
+    // 分段线性变换，控制点按输入值排序，两端之外保持端点输出
+    Piecewise(Vec<(f32, f32)>),
+}
+
+impl IntensityTransform {
+    // 对归一化的输入求变换结果，位平面与百分位拉伸不能按归一化值计算，保持不变
+    pub fn evaluate(&self, r: f32) -> f32 {
+        let s = match self {
+            IntensityTransform::Gamma(gamma) => r.powf(*gamma),
+            IntensityTransform::Log(c) if *c > 0.0 => (1.0 + c * r).ln() / (1.0 + c).ln(),
+            IntensityTransform::InverseLog(c) if *c > 0.0 => ((1.0 + c).powf(r) - 1.0) / c,
+            IntensityTransform::ContrastStretch { low, high } => {
+                if high > low {
+                    (r - low) / (high - low)
+                } else if r >= *low {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            IntensityTransform::Piecewise(points) => piecewise(points, r),
+            _ => r,
+        };
+
+        s.clamp(0.0, 1.0)
+    }
+
+    // 预先计算 levels 级查找表
+    pub fn lut(&self, levels: usize) -> Vec<u32> {
+        let max_level = levels.saturating_sub(1) as u32;
+        if let IntensityTransform::BitPlane(bit) = self {
+            return (0..levels as u32)
+                .map(|v| {
+                    if *bit < 32 && (v >> bit) & 1 == 1 {
+                        max_level
+                    } else {
+                        0
+                    }
+                })
+                .collect();
+        }
+
+        (0..levels)
+            .map(|v| {
+                let r = v as f32 / max_level.max(1) as f32;
+                (self.evaluate(r) * max_level as f32).round() as u32
+            })
+            .collect()
+    }
+}
+
+// 分段线性插值
+fn piecewise(points: &[(f32, f32)], r: f32) -> f32 {
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    match points.iter().position(|p| p.0 >= r) {
+        None => points.last().map_or(r, |p| p.1),
+        Some(0) => points[0].1,
+        Some(i) => {
+            let (x0, y0) = points[i - 1];
+            let (x1, y1) = points[i];
+            if x1 > x0 {
+                y0 + (y1 - y0) * (r - x0) / (x1 - x0)
+            } else {
+                y1
+            }
+        }
+    }
+}
+
+// 直方图的百分位数对应的归一化灰度范围
+pub fn percentile_range(histogram: &[u32], low: f32, high: f32) -> (f32, f32) {
+    let n: u64 = histogram.iter().map(|v| *v as u64).sum();
+    let max_level = histogram.len().saturating_sub(1).max(1) as f32;
+    let level = |percent: f32| {
+        let target = (percent.clamp(0.0, 100.0) as f64 / 100.0 * n as f64).ceil() as u64;
+        let mut sum = 0_u64;
+        for (v, count) in histogram.iter().enumerate() {
+            sum += *count as u64;
+            if sum >= target.max(1) {
+                return v;
+            }
+        }
+        histogram.len().saturating_sub(1)
+    };
+
+    (
+        level(low) as f32 / max_level,
+        level(high) as f32 / max_level,
+    )
+}
+
+// 将变换落实为某一通道的查找表，百分位拉伸由该通道直方图确定范围
+fn channel_lut(transform: &IntensityTransform, values: &[u32], levels: usize) -> Vec<u32> {
+    match transform {
+        IntensityTransform::PercentileStretch { low, high } => {
+            let mut histogram = vec![0_u32; levels];
+            for v in values {
+                histogram[*v as usize] += 1;
+            }
+
+            let (low, high) = percentile_range(&histogram, *low, *high);
+            IntensityTransform::ContrastStretch { low, high }.lut(levels)
+        }
+        _ => transform.lut(levels),
+    }
+}
+
+// 对图像应用灰度变换，按处理方式作用于亮度或 RGB 各通道，保留颜色类型与透明通道
+pub fn apply_transform(
+    image: &DynamicImage,
+    transform: &IntensityTransform,
+    mode: EqualizeMode,
+) -> DynamicImage {
+    // 8位图像逐通道直接查表
+    if !is_high_depth(image) && (mode == EqualizeMode::Rgb || !image.color().has_color()) {
+        return map_channels(image, false, |channel| {
+            let values: Vec<u32> = channel.iter().map(|v| *v as u32).collect();
+            let lut = channel_lut(transform, &values, 256);
+            channel.iter().map(|v| lut[*v as usize] as u8).collect()
+        });
+    }
+
+    let mode = if image.color().has_color() {
+        mode
+    } else {
+        EqualizeMode::YCbCr
+    };
+
+    map_color_channels(image, mode, |_, values, levels| {
+        let lut = channel_lut(transform, values, levels);
+        values.iter().map(|v| lut[*v as usize]).collect()
+    })
+}
@@ -0,0 +1,404 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+use image::{DynamicImage, GrayImage, Rgba, RgbaImage};
+
+use super::components::{Connectivity, LabelImage};
+use super::contour::line_points;
+use super::edge::{gradient, GradientOperator};
+use super::image_util::BorderMode;
+
+// 优先队列元素，值小者先出，值相同时先入队者先出
+struct Entry {
+    value: f32,
+    order: u64,
+    index: usize,
+    label: u32,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .value
+            .total_cmp(&self.value)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+// 像素的邻居下标
+fn neighbors(
+    index: usize,
+    width: usize,
+    height: usize,
+    connectivity: Connectivity,
+) -> impl Iterator<Item = usize> {
+    let (x, y) = ((index % width) as i64, (index / width) as i64);
+    connectivity.offsets().iter().filter_map(move |(dx, dy)| {
+        let (nx, ny) = (x + dx, y + dy);
+        if nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height {
+            Some(ny as usize * width + nx as usize)
+        } else {
+            None
+        }
+    })
+}
+
+// 一维平方距离变换（Felzenszwalb 下包络抛物线）
+fn distance_1d(f: &[f32]) -> Vec<f32> {
+    let n = f.len();
+    let mut d = vec![0.0; n];
+    let mut v = vec![0_usize; n];
+    let mut z = vec![0.0_f32; n + 1];
+    let mut k = 0;
+    z[0] = f32::MIN;
+    z[1] = f32::MAX;
+
+    let intersection = |q: usize, p: usize| {
+        ((f[q] + (q * q) as f32) - (f[p] + (p * p) as f32)) / (2.0 * q as f32 - 2.0 * p as f32)
+    };
+
+    for q in 1..n {
+        let mut s = intersection(q, v[k]);
+        while s <= z[k] {
+            k -= 1;
+            s = intersection(q, v[k]);
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = f32::MAX;
+    }
+
+    k = 0;
+    for (q, d) in d.iter_mut().enumerate() {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+        let p = v[k];
+        *d = (q as f32 - p as f32) * (q as f32 - p as f32) + f[p];
+    }
+
+    d
+}
+
+// 欧氏距离变换，前景像素到最近背景像素的精确距离
+pub fn distance_transform(binary: &GrayImage) -> Vec<f32> {
+    let width = binary.width() as usize;
+    let height = binary.height() as usize;
+    // 没有背景像素时距离取一个足够大的值
+    let far = ((width * width + height * height) as f32) * 4.0;
+
+    let mut data: Vec<f32> = binary
+        .as_raw()
+        .iter()
+        .map(|v| if *v > 0 { far } else { 0.0 })
+        .collect();
+
+    // 先按列再按行
+    for x in 0..width {
+        let column: Vec<f32> = (0..height).map(|y| data[y * width + x]).collect();
+        for (y, v) in distance_1d(&column).into_iter().enumerate() {
+            data[y * width + x] = v;
+        }
+    }
+    for row in data.chunks_mut(width.max(1)) {
+        let result = distance_1d(row);
+        row.copy_from_slice(&result);
+    }
+
+    data.into_iter().map(|v| v.sqrt()).collect()
+}
+
+// 以 depth 为深度的腐蚀重建，深度小于 depth 的极小值被填平
+fn fill_minima(
+    values: &[f32],
+    width: usize,
+    height: usize,
+    depth: f32,
+    connectivity: Connectivity,
+) -> Vec<f32> {
+    let mut result: Vec<f32> = values.iter().map(|v| v + depth).collect();
+    let mut heap = BinaryHeap::with_capacity(values.len());
+    for (index, value) in result.iter().enumerate() {
+        heap.push(Entry {
+            value: *value,
+            order: 0,
+            index,
+            label: 0,
+        });
+    }
+
+    while let Some(entry) = heap.pop() {
+        if entry.value > result[entry.index] {
+            continue;
+        }
+
+        for n in neighbors(entry.index, width, height, connectivity) {
+            let value = entry.value.max(values[n]);
+            if value < result[n] {
+                result[n] = value;
+                heap.push(Entry {
+                    value,
+                    order: 0,
+                    index: n,
+                    label: 0,
+                });
+            }
+        }
+    }
+
+    result
+}
+
+// 区域极小值标记，depth 大于 0 时只保留深度不小于 depth 的极小值
+pub fn minima_markers(
+    values: &[f32],
+    width: usize,
+    height: usize,
+    depth: f32,
+    connectivity: Connectivity,
+) -> LabelImage {
+    let values = if depth > 0.0 {
+        fill_minima(values, width, height, depth, connectivity)
+    } else {
+        values.to_vec()
+    };
+
+    let mut labels = vec![0_u32; width * height];
+    let mut visited = vec![false; width * height];
+    let mut count = 0;
+
+    // 逐个检查等值平台，平台周围没有更小的值即为极小值
+    for start in 0..values.len() {
+        if visited[start] {
+            continue;
+        }
+
+        let value = values[start];
+        let mut plateau = vec![start];
+        let mut queue = VecDeque::from([start]);
+        let mut is_minimum = true;
+        visited[start] = true;
+
+        while let Some(index) = queue.pop_front() {
+            for n in neighbors(index, width, height, connectivity) {
+                if values[n] < value {
+                    is_minimum = false;
+                } else if values[n] == value && !visited[n] {
+                    visited[n] = true;
+                    plateau.push(n);
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        if is_minimum {
+            count += 1;
+            for index in plateau {
+                labels[index] = count;
+            }
+        }
+    }
+
+    LabelImage {
+        width,
+        height,
+        labels,
+        count,
+    }
+}
+
+// 由手绘笔画生成标记，每条笔画为一个标记
+pub fn markers_from_strokes(
+    strokes: &[Vec<(i32, i32)>],
+    width: usize,
+    height: usize,
+) -> LabelImage {
+    let mut labels = vec![0_u32; width * height];
+    let mut count = 0;
+
+    for stroke in strokes.iter().filter(|s| !s.is_empty()) {
+        count += 1;
+
+        let segments = stroke.windows(2).map(|w| (w[0], w[1]));
+        let points = segments
+            .flat_map(|(a, b)| line_points(a, b))
+            .chain(std::iter::once(stroke[0]));
+        for (x, y) in points {
+            if x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height {
+                labels[y as usize * width + x as usize] = count;
+            }
+        }
+    }
+
+    LabelImage {
+        width,
+        height,
+        labels,
+        count,
+    }
+}
+
+// 标记控制的分水岭（Meyer 淹没算法）
+// 从标记出发按值从小到大淹没，每个像素取得首个淹没到它的标记，mask 为 0 的像素保持为背景
+pub fn watershed(
+    values: &[f32],
+    markers: &LabelImage,
+    mask: Option<&GrayImage>,
+    connectivity: Connectivity,
+) -> LabelImage {
+    let width = markers.width;
+    let height = markers.height;
+    let allowed = |index: usize| match mask {
+        Some(mask) => mask.as_raw()[index] > 0,
+        None => true,
+    };
+
+    let mut labels: Vec<u32> = markers
+        .labels
+        .iter()
+        .enumerate()
+        .map(|(index, label)| if allowed(index) { *label } else { 0 })
+        .collect();
+    let mut queued: Vec<bool> = labels.iter().map(|label| *label > 0).collect();
+
+    let mut heap = BinaryHeap::new();
+    let mut order = 0;
+    for (index, label) in labels.iter().enumerate() {
+        if *label == 0 {
+            continue;
+        }
+
+        for n in neighbors(index, width, height, connectivity) {
+            if !queued[n] && allowed(n) {
+                queued[n] = true;
+                order += 1;
+                heap.push(Entry {
+                    value: values[n],
+                    order,
+                    index: n,
+                    label: *label,
+                });
+            }
+        }
+    }
+
+    while let Some(entry) = heap.pop() {
+        labels[entry.index] = entry.label;
+
+        for n in neighbors(entry.index, width, height, connectivity) {
+            if !queued[n] && allowed(n) {
+                queued[n] = true;
+                order += 1;
+                heap.push(Entry {
+                    // 水位不会下降
+                    value: values[n].max(entry.value),
+                    order,
+                    index: n,
+                    label: entry.label,
+                });
+            }
+        }
+    }
+
+    LabelImage {
+        width,
+        height,
+        labels,
+        count: markers.count,
+    }
+}
+
+// 在梯度幅值图上做分水岭，未给出标记时使用梯度的区域极小值
+pub fn watershed_gradient(
+    image: &DynamicImage,
+    markers: Option<&LabelImage>,
+    depth: f32,
+    border: BorderMode,
+) -> LabelImage {
+    let gradient = gradient(image, GradientOperator::Sobel, border);
+    let (width, height) = (gradient.width, gradient.height);
+
+    let minima;
+    let markers = match markers {
+        Some(markers) => markers,
+        None => {
+            minima = minima_markers(
+                &gradient.magnitude,
+                width,
+                height,
+                depth,
+                Connectivity::Eight,
+            );
+            &minima
+        }
+    };
+
+    watershed(&gradient.magnitude, markers, None, Connectivity::Eight)
+}
+
+// 在距离变换上做分水岭，用于分离粘连的颗粒
+// 未给出标记时使用距离的区域极大值，即各颗粒的中心
+pub fn watershed_distance(
+    binary: &GrayImage,
+    markers: Option<&LabelImage>,
+    depth: f32,
+) -> LabelImage {
+    let (width, height) = (binary.width() as usize, binary.height() as usize);
+    let values: Vec<f32> = distance_transform(binary).into_iter().map(|v| -v).collect();
+
+    let minima;
+    let markers = match markers {
+        Some(markers) => markers,
+        None => {
+            minima = minima_markers(&values, width, height, depth, Connectivity::Eight);
+            &minima
+        }
+    };
+
+    watershed(&values, markers, Some(binary), Connectivity::Eight)
+}
+
+// 分水岭边界：与 4 邻域中不同区域相邻的像素
+pub fn watershed_lines(labels: &LabelImage) -> Vec<bool> {
+    let (width, height) = (labels.width, labels.height);
+
+    (0..labels.labels.len())
+        .map(|index| {
+            let label = labels.labels[index];
+            label > 0
+                && neighbors(index, width, height, Connectivity::Four).any(|n| {
+                    let other = labels.labels[n];
+                    other > 0 && other != label
+                })
+        })
+        .collect()
+}
+
+// 在图像上叠加分水岭边界
+pub fn draw_watershed(image: &DynamicImage, labels: &LabelImage, color: Rgba<u8>) -> RgbaImage {
+    let mut image = image.to_rgba8();
+    let width = labels.width;
+
+    for (index, line) in watershed_lines(labels).into_iter().enumerate() {
+        if line {
+            image.put_pixel((index % width) as u32, (index / width) as u32, color);
+        }
+    }
+
+    image
+}